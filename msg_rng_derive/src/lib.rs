@@ -0,0 +1,194 @@
+//! Derive macros for `msg_rng`.
+//!
+//! Enable the `derive` feature of `msg_rng` instead of depending on this
+//! crate directly; the generated code refers to `msg_rng`'s re-export of
+//! `rand`.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::{
+    Attribute, Data, DeriveInput, Error, Expr, Fields, Lit, Result, parse_macro_input, parse_quote,
+};
+
+/// Derive `Distribution<Self>` for `StandardUniform`.
+///
+/// The generated code refers to `msg_rng`'s re-export of `rand`, so use this
+/// through `msg_rng`'s `derive` feature, where it is documented with examples.
+#[proc_macro_derive(Random, attributes(weight, range))]
+pub fn derive_random(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn expand(input: &DeriveInput) -> Result<TokenStream2> {
+    let name = &input.ident;
+    let body = match &input.data {
+        Data::Struct(data) => construct(&quote!(#name), &data.fields)?,
+        Data::Enum(data) => {
+            if data.variants.is_empty() {
+                return Err(Error::new_spanned(
+                    name,
+                    "cannot derive `Random` for an enum without variants",
+                ));
+            }
+
+            let mut weights = Vec::with_capacity(data.variants.len());
+            let mut arms = Vec::with_capacity(data.variants.len());
+            for variant in &data.variants {
+                let ident = &variant.ident;
+                weights.push(variant_weight(&variant.attrs)?.unwrap_or(1.0));
+                arms.push(construct(&quote!(#name::#ident), &variant.fields)?);
+            }
+            if weights.iter().all(|&w| w == 0.0) {
+                return Err(Error::new_spanned(
+                    name,
+                    "at least one variant needs a positive `#[weight]`",
+                ));
+            }
+
+            // Zero-weight variants can never be picked, so leave them out entirely
+            let positive: Vec<_> = weights
+                .iter()
+                .zip(&arms)
+                .filter(|(weight, _)| **weight > 0.0)
+                .collect();
+            let total: f64 = weights.iter().sum();
+            let (checks, fallback) = positive.split_at(positive.len() - 1);
+            let checks = checks.iter().map(|(weight, arm)| {
+                quote! {
+                    if roll < #weight {
+                        return #arm;
+                    }
+                    roll -= #weight;
+                }
+            });
+            let fallback = fallback[0].1;
+            quote! {
+                let mut roll = rng.random::<f64>() * #total;
+                #(#checks)*
+                #fallback
+            }
+        }
+        Data::Union(_) => {
+            return Err(Error::new_spanned(
+                name,
+                "cannot derive `Random` for a union",
+            ));
+        }
+    };
+
+    let mut generics = input.generics.clone();
+    let type_params: Vec<_> = generics.type_params().map(|p| p.ident.clone()).collect();
+    let where_clause = generics.make_where_clause();
+    for param in type_params {
+        where_clause.predicates.push(parse_quote! {
+            ::msg_rng::rand::distr::StandardUniform: ::msg_rng::rand::distr::Distribution<#param>
+        });
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::msg_rng::rand::distr::Distribution<#name #ty_generics>
+            for ::msg_rng::rand::distr::StandardUniform
+            #where_clause
+        {
+            #[allow(unused_mut, unused_variables, clippy::float_cmp)]
+            fn sample<R: ::msg_rng::rand::Rng + ?Sized>(&self, rng: &mut R) -> #name #ty_generics {
+                use ::msg_rng::rand::RngExt as _;
+                #body
+            }
+        }
+    })
+}
+
+/// Build an expression constructing `path` with randomly sampled fields.
+fn construct(path: &TokenStream2, fields: &Fields) -> Result<TokenStream2> {
+    match fields {
+        Fields::Unit => Ok(quote!(#path)),
+        Fields::Named(named) => {
+            let values = named
+                .named
+                .iter()
+                .map(|field| {
+                    let ident = &field.ident;
+                    let value = field_value(field)?;
+                    Ok(quote!(#ident: #value))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            Ok(quote!(#path { #(#values),* }))
+        }
+        Fields::Unnamed(unnamed) => {
+            let values = unnamed
+                .unnamed
+                .iter()
+                .map(field_value)
+                .collect::<Result<Vec<_>>>()?;
+            Ok(quote!(#path(#(#values),*)))
+        }
+    }
+}
+
+fn field_value(field: &syn::Field) -> Result<TokenStream2> {
+    let mut range = None;
+    for attr in &field.attrs {
+        if attr.path().is_ident("range") {
+            if range.is_some() {
+                return Err(Error::new_spanned(attr, "duplicate `#[range]` attribute"));
+            }
+            range = Some(attr.parse_args::<Expr>()?);
+        } else if attr.path().is_ident("weight") {
+            return Err(Error::new_spanned(
+                attr,
+                "`#[weight]` is only allowed on enum variants",
+            ));
+        }
+    }
+
+    let span = field.ty.span();
+    Ok(match range {
+        Some(range) => quote_spanned!(span=> rng.random_range(#range)),
+        None => quote_spanned!(span=> rng.random()),
+    })
+}
+
+fn variant_weight(attrs: &[Attribute]) -> Result<Option<f64>> {
+    let mut weight = None;
+    for attr in attrs {
+        if attr.path().is_ident("range") {
+            return Err(Error::new_spanned(
+                attr,
+                "`#[range]` is only allowed on fields",
+            ));
+        }
+        if !attr.path().is_ident("weight") {
+            continue;
+        }
+        if weight.is_some() {
+            return Err(Error::new_spanned(attr, "duplicate `#[weight]` attribute"));
+        }
+
+        let lit = attr.parse_args::<Lit>()?;
+        let value = match &lit {
+            Lit::Int(int) => int.base10_parse::<f64>()?,
+            Lit::Float(float) => float.base10_parse::<f64>()?,
+            _ => {
+                return Err(Error::new_spanned(
+                    lit,
+                    "`#[weight]` expects a number literal",
+                ));
+            }
+        };
+        if !(value >= 0.0 && value.is_finite()) {
+            return Err(Error::new_spanned(
+                lit,
+                "`#[weight]` must be a non-negative finite number",
+            ));
+        }
+        weight = Some(value);
+    }
+    Ok(weight)
+}
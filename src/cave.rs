@@ -0,0 +1,358 @@
+//! Cellular automaton cave generation.
+//!
+//! The grid starts as random noise and is smoothed by a birth/survival rule,
+//! like Conway's Game of Life but counting walls among the eight neighbors.
+//! Small regions are then culled and the remaining caves optionally joined, so
+//! a layout is fully defined by the seed and the settings.
+
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+use rand::RngExt;
+
+use crate::{Grid, RngSource};
+
+const NEIGHBORS: [IVec2; 8] = [
+    IVec2::new(-1, -1),
+    IVec2::new(0, -1),
+    IVec2::new(1, -1),
+    IVec2::new(-1, 0),
+    IVec2::new(1, 0),
+    IVec2::new(-1, 1),
+    IVec2::new(0, 1),
+    IVec2::new(1, 1),
+];
+
+const ORTHOGONAL: [IVec2; 4] = [IVec2::X, IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y];
+
+/// How [`CellularCave`] makes sure every open cell can be reached.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Reflect)]
+pub enum Connectivity {
+    /// Leave separate caves as they are.
+    #[default]
+    None,
+    /// Fill in every cave except the largest.
+    KeepLargest,
+    /// Dig the shortest tunnel from each cave to the ones already joined,
+    /// starting from the largest.
+    Tunnel,
+}
+
+/// Cellular automaton cave generator.
+///
+/// Generated grids hold `true` for open floor and `false` for wall. Cells
+/// beyond the edge count as wall, so caves close up toward the border.
+///
+/// # Examples
+///
+/// ```rust
+/// use bevy::prelude::*;
+/// use msg_rng::prelude::*;
+/// use msg_rng::{CellularCave, Connectivity};
+///
+/// let mut rng = GlobalRng::seeded(42);
+/// let mut level = rng.fork_stream(3);
+///
+/// let cave = CellularCave::new(UVec2::new(80, 50))
+///     .with_fill(0.45)
+///     .with_iterations(5)
+///     .with_min_region(20, 10)
+///     .with_connectivity(Connectivity::Tunnel)
+///     .generate(&mut level);
+///
+/// let floor = cave.count(|&open| open);
+/// assert!(floor > 0 && floor < 80 * 50);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct CellularCave {
+    size: UVec2,
+    fill: f32,
+    birth: u16,
+    survival: u16,
+    iterations: u32,
+    min_floor: usize,
+    min_wall: usize,
+    connectivity: Connectivity,
+}
+
+impl CellularCave {
+    /// Create a generator for a grid of `size` cells.
+    ///
+    /// Defaults to a 45% wall fill, the B5678/S45678 rule (a wall is born
+    /// with at least 5 wall neighbors and survives with at least 4), 5
+    /// iterations, no culling and no connectivity pass.
+    #[must_use]
+    pub fn new(size: UVec2) -> Self {
+        Self {
+            size,
+            fill: 0.45,
+            birth: rule_mask([5, 6, 7, 8]),
+            survival: rule_mask([4, 5, 6, 7, 8]),
+            iterations: 5,
+            min_floor: 0,
+            min_wall: 0,
+            connectivity: Connectivity::None,
+        }
+    }
+
+    /// Set the chance for each cell to start as wall.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `fill` is not in `[0.0, 1.0]`.
+    #[must_use]
+    pub fn with_fill(mut self, fill: f32) -> Self {
+        assert!((0.0..=1.0).contains(&fill), "fill must be in [0.0, 1.0]");
+        self.fill = fill;
+        self
+    }
+
+    /// Set the rule as the wall neighbor counts that turn floor into wall
+    /// (`birth`) and that keep a wall standing (`survival`).
+    ///
+    /// # Panics
+    ///
+    /// Panics if any count is above 8.
+    #[must_use]
+    pub fn with_rules(
+        mut self,
+        birth: impl IntoIterator<Item = u8>,
+        survival: impl IntoIterator<Item = u8>,
+    ) -> Self {
+        self.birth = rule_mask(birth);
+        self.survival = rule_mask(survival);
+        self
+    }
+
+    /// Set how many times the rule is applied. More iterations give smoother
+    /// caves.
+    #[must_use]
+    pub fn with_iterations(mut self, iterations: u32) -> Self {
+        self.iterations = iterations;
+        self
+    }
+
+    /// Fill in floor regions smaller than `floor` cells and open up wall
+    /// regions smaller than `wall` cells, removing pockets and pillars.
+    #[must_use]
+    pub fn with_min_region(mut self, floor: usize, wall: usize) -> Self {
+        self.min_floor = floor;
+        self.min_wall = wall;
+        self
+    }
+
+    /// Set how separate caves are joined up.
+    #[must_use]
+    pub fn with_connectivity(mut self, connectivity: Connectivity) -> Self {
+        self.connectivity = connectivity;
+        self
+    }
+
+    /// Generate the cave.
+    pub fn generate<R: RngSource + ?Sized>(&self, rng: &mut R) -> Grid<bool> {
+        let rng = rng.rng_mut();
+        let fill = f64::from(self.fill);
+        let mut open = Grid::from_fn(self.size, |_| !rng.random_bool(fill));
+
+        for _ in 0..self.iterations {
+            open = self.step(&open);
+        }
+        cull(&mut open, false, self.min_wall);
+        cull(&mut open, true, self.min_floor);
+
+        match self.connectivity {
+            Connectivity::None => {}
+            Connectivity::KeepLargest => {
+                let mut caves = regions(&open, true);
+                caves.sort_by_key(|cave| std::cmp::Reverse(cave.len()));
+                for pos in caves.iter().skip(1).flatten() {
+                    open[*pos] = false;
+                }
+            }
+            Connectivity::Tunnel => tunnel(&mut open),
+        }
+        open
+    }
+
+    fn step(&self, open: &Grid<bool>) -> Grid<bool> {
+        Grid::from_fn(open.size(), |pos| {
+            let walls = NEIGHBORS
+                .iter()
+                .filter(|&&d| !open.get(pos + d).copied().unwrap_or(false))
+                .count();
+            let rule = if open[pos] { self.birth } else { self.survival };
+            rule & (1 << walls) == 0
+        })
+    }
+}
+
+fn rule_mask(counts: impl IntoIterator<Item = u8>) -> u16 {
+    counts.into_iter().fold(0, |mask, count| {
+        assert!(count <= 8, "neighbor counts must be at most 8, got {count}");
+        mask | 1 << count
+    })
+}
+
+/// Find the orthogonally connected regions of cells equal to `value`.
+fn regions(grid: &Grid<bool>, value: bool) -> Vec<Vec<IVec2>> {
+    let mut seen = grid.map(|&cell| cell != value);
+    let mut regions = Vec::new();
+    for (start, &cell) in grid.iter() {
+        if cell != value || seen[start] {
+            continue;
+        }
+        seen[start] = true;
+        let mut region = vec![start];
+        let mut i = 0;
+        while let Some(&pos) = region.get(i) {
+            for d in ORTHOGONAL {
+                if seen.get(pos + d) == Some(&false) {
+                    seen[pos + d] = true;
+                    region.push(pos + d);
+                }
+            }
+            i += 1;
+        }
+        regions.push(region);
+    }
+    regions
+}
+
+/// Flip regions of `value` smaller than `min` cells.
+fn cull(grid: &mut Grid<bool>, value: bool, min: usize) {
+    for region in regions(grid, value) {
+        if region.len() < min {
+            for pos in region {
+                grid[pos] = !value;
+            }
+        }
+    }
+}
+
+/// Join all floor regions to the largest one with shortest tunnels.
+fn tunnel(open: &mut Grid<bool>) {
+    let mut caves = regions(open, true);
+    caves.sort_by_key(|cave| std::cmp::Reverse(cave.len()));
+    let mut region = Grid::new(open.size(), None);
+    for (id, cave) in caves.iter().enumerate() {
+        for &pos in cave {
+            region[pos] = Some(id);
+        }
+    }
+    let mut joined = vec![false; caves.len()];
+    let mut frontier: Vec<IVec2> = caves.first().cloned().unwrap_or_default();
+    if let Some(first) = joined.first_mut() {
+        *first = true;
+    }
+
+    while joined.iter().any(|&j| !j) {
+        // Breadth-first search from the joined caves through walls
+        let mut from = Grid::new(open.size(), None);
+        let mut queue: VecDeque<IVec2> = frontier.iter().copied().collect();
+        for &pos in &frontier {
+            from[pos] = Some(pos);
+        }
+        let (end, id) = loop {
+            let Some(pos) = queue.pop_front() else {
+                return;
+            };
+            if let Some(id) = region[pos].filter(|&id| !joined[id]) {
+                break (pos, id);
+            }
+            for d in ORTHOGONAL {
+                let next = pos + d;
+                if from.get(next) == Some(&None) {
+                    from[next] = Some(pos);
+                    queue.push_back(next);
+                }
+            }
+        };
+
+        let mut pos = end;
+        while let Some(prev) = from[pos].filter(|&prev| prev != pos) {
+            open[prev] = true;
+            frontier.push(prev);
+            pos = prev;
+        }
+        joined[id] = true;
+        frontier.extend(&caves[id]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EntityRng, GlobalRng};
+
+    #[test]
+    fn caves_are_reproducible() {
+        let cave = CellularCave::new(UVec2::new(40, 30)).with_connectivity(Connectivity::Tunnel);
+
+        let a = cave.generate(&mut GlobalRng::seeded(1));
+        let b = cave.generate(&mut EntityRng::seeded(1));
+        let c = cave.generate(&mut GlobalRng::seeded(2));
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+        assert_ne!(
+            a,
+            cave.clone()
+                .with_fill(0.5)
+                .generate(&mut GlobalRng::seeded(1))
+        );
+    }
+
+    #[test]
+    fn rules_decide_which_walls_survive() {
+        let mut rng = GlobalRng::seeded(3);
+        let solid = CellularCave::new(UVec2::new(10, 10)).with_fill(1.0);
+
+        assert_eq!(solid.generate(&mut rng).count(|&open| open), 0);
+        let crumbling = solid.with_rules([], []).with_iterations(1);
+        assert_eq!(crumbling.generate(&mut rng).count(|&open| open), 100);
+
+        // Without iterations the grid is the raw fill
+        let noise = CellularCave::new(UVec2::new(100, 100))
+            .with_fill(0.3)
+            .with_iterations(0)
+            .generate(&mut rng);
+        assert!((6700..7300).contains(&noise.count(|&open| open)));
+    }
+
+    #[test]
+    fn small_regions_are_culled() {
+        let cave = CellularCave::new(UVec2::new(60, 40))
+            .with_min_region(30, 8)
+            .generate(&mut GlobalRng::seeded(4));
+
+        assert!(regions(&cave, true).iter().all(|r| r.len() >= 30));
+        assert!(regions(&cave, false).iter().all(|r| r.len() >= 8));
+    }
+
+    #[test]
+    fn connectivity_leaves_a_single_cave() {
+        let size = UVec2::new(60, 40);
+        let plain = CellularCave::new(size).generate(&mut GlobalRng::seeded(5));
+        let largest = CellularCave::new(size)
+            .with_connectivity(Connectivity::KeepLargest)
+            .generate(&mut GlobalRng::seeded(5));
+        let tunneled = CellularCave::new(size)
+            .with_connectivity(Connectivity::Tunnel)
+            .generate(&mut GlobalRng::seeded(5));
+
+        let sizes: Vec<usize> = regions(&plain, true).iter().map(Vec::len).collect();
+        assert!(sizes.len() > 1);
+        assert_eq!(regions(&largest, true).len(), 1);
+        assert_eq!(
+            largest.count(|&open| open),
+            sizes.iter().copied().max().unwrap()
+        );
+        assert_eq!(regions(&tunneled, true).len(), 1);
+        assert!(tunneled.count(|&open| open) > plain.count(|&open| open));
+    }
+
+    #[test]
+    #[should_panic(expected = "at most 8")]
+    fn impossible_rules_panic() {
+        let _ = CellularCave::new(UVec2::ONE).with_rules([9], []);
+    }
+}
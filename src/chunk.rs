@@ -0,0 +1,178 @@
+//! Per-chunk seeds for infinite procedural worlds.
+//!
+//! Forking the global RNG hands out seeds in call order, so chunks loaded in a
+//! different order would get different content. [`ChunkSeeds`] hashes the
+//! world seed with the chunk coordinate instead, so a chunk always regenerates
+//! identically after unloading.
+
+use bevy::prelude::*;
+
+use crate::hash::{HashKey, POSITION_STREAM, hash_combine, hash_u64};
+use crate::{EntityRng, GlobalRng, PositionRng};
+
+/// Stream id mixed into the world seed to derive chunk seeds.
+const CHUNK_STREAM: u64 = 0xC4_0A4C;
+
+/// Seeds and RNGs per chunk coordinate, derived from a world seed.
+///
+/// Chunks can be keyed by anything implementing [`HashKey`], typically
+/// `IVec2` or `IVec3`. Use [`layer`](Self::layer) to give each generation
+/// pass its own independent streams, so adding creatures to a chunk never
+/// shifts its terrain.
+///
+/// # Examples
+///
+/// ```rust
+/// use bevy::prelude::*;
+/// use msg_rng::prelude::*;
+/// use msg_rng::ChunkSeeds;
+///
+/// let global = GlobalRng::seeded(42);
+/// let seeds = ChunkSeeds::from_global(&global);
+/// let chunk = IVec2::new(-3, 17);
+///
+/// let mut terrain = seeds.layer("terrain").rng(chunk);
+/// let mut creatures = seeds.layer("creatures").rng(chunk);
+/// let height: f32 = terrain.range(0.0..100.0);
+/// let wolves: u32 = creatures.range(0..4);
+///
+/// // Reloading the chunk later gives the same terrain
+/// let mut reloaded = seeds.layer("terrain").rng(chunk);
+/// assert_eq!(reloaded.range::<f32, _>(0.0..100.0), height);
+/// ```
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ChunkSeeds {
+    seed: u64,
+}
+
+impl ChunkSeeds {
+    /// Create chunk seeds for a world seed.
+    #[must_use]
+    pub fn new(world_seed: u64) -> Self {
+        Self {
+            seed: hash_combine(world_seed, CHUNK_STREAM),
+        }
+    }
+
+    /// Create chunk seeds from the global RNG's seed.
+    ///
+    /// This does not consume randomness from the global RNG.
+    #[must_use]
+    pub fn from_global(global: &GlobalRng) -> Self {
+        Self::new(global.seed())
+    }
+
+    /// Derive independent chunk seeds for a generation layer, e.g.
+    /// `"terrain"`, `"structures"` or `"creatures"`.
+    ///
+    /// Layers can be nested for finer sub-streams.
+    #[must_use]
+    pub fn layer<L: HashKey>(&self, layer: L) -> Self {
+        Self {
+            seed: hash_u64(self.seed, layer),
+        }
+    }
+
+    /// Get the seed for `chunk`.
+    #[must_use]
+    pub fn seed<C: HashKey>(&self, chunk: C) -> u64 {
+        hash_u64(self.seed, chunk)
+    }
+
+    /// Create a fresh RNG for `chunk`.
+    ///
+    /// Every call starts the same sequence, so generation code can create it
+    /// whenever the chunk is (re)loaded.
+    #[must_use]
+    pub fn rng<C: HashKey>(&self, chunk: C) -> EntityRng {
+        EntityRng::seeded(self.seed(chunk))
+    }
+
+    /// Get stateless randomness for `chunk`, for quick per-chunk decisions.
+    ///
+    /// This is a separate stream, independent of [`rng`](Self::rng).
+    #[must_use]
+    pub fn position<C: HashKey>(&self, chunk: C) -> PositionRng {
+        PositionRng::at(hash_combine(self.seed, POSITION_STREAM), chunk)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::RngExt;
+
+    use super::*;
+    use crate::RngSource;
+
+    fn generate(seeds: &ChunkSeeds, chunk: IVec2) -> Vec<u32> {
+        let mut rng = seeds.layer("terrain").rng(chunk);
+        (0..8).map(|_| rng.range(0..1000)).collect()
+    }
+
+    #[test]
+    fn chunks_regenerate_regardless_of_load_order() {
+        let seeds = ChunkSeeds::new(1);
+        let chunks: Vec<IVec2> = (-2..3)
+            .flat_map(|x| (-2..3).map(move |y| IVec2::new(x, y)))
+            .collect();
+
+        let forward: Vec<Vec<u32>> = chunks.iter().map(|&c| generate(&seeds, c)).collect();
+        let backward: Vec<Vec<u32>> = chunks
+            .iter()
+            .rev()
+            .map(|&c| generate(&seeds, c))
+            .rev()
+            .collect();
+        assert_eq!(forward, backward);
+
+        // Every chunk gets its own content
+        for (i, a) in forward.iter().enumerate() {
+            assert!(forward[i + 1..].iter().all(|b| a != b));
+        }
+    }
+
+    #[test]
+    fn layers_are_independent() {
+        let seeds = ChunkSeeds::new(2);
+        let chunk = IVec3::new(4, -1, 9);
+
+        assert_ne!(
+            seeds.layer("terrain").seed(chunk),
+            seeds.layer("creatures").seed(chunk)
+        );
+        assert_ne!(seeds.layer("terrain").seed(chunk), seeds.seed(chunk));
+        assert_ne!(
+            seeds.layer("terrain").layer(0_u32).seed(chunk),
+            seeds.layer("terrain").layer(1_u32).seed(chunk)
+        );
+        // 2D and 3D coordinates don't alias
+        assert_ne!(
+            seeds.seed(IVec2::new(4, -1)),
+            seeds.seed(IVec3::new(4, -1, 0))
+        );
+    }
+
+    #[test]
+    fn seeds_follow_the_world_seed() {
+        let global = GlobalRng::seeded(3);
+        let seeds = ChunkSeeds::from_global(&global);
+
+        assert_eq!(seeds, ChunkSeeds::new(3));
+        assert_ne!(seeds, ChunkSeeds::new(4));
+        assert_eq!(seeds.rng(IVec2::ZERO).seed(), seeds.seed(IVec2::ZERO));
+        // Chunk seeds don't collide with the global `at()` stream
+        assert_ne!(seeds.position(IVec2::ZERO), global.at(IVec2::ZERO));
+    }
+
+    #[test]
+    fn position_is_independent_of_the_chunk_rng() {
+        let seeds = ChunkSeeds::new(5);
+        for chunk in [IVec2::ZERO, IVec2::new(7, -2)] {
+            let mut rng = seeds.rng(chunk);
+            let position = seeds.position(chunk);
+            assert_ne!(position.u64(), seeds.seed(chunk));
+            assert_ne!(position.u64(), rng.rng_mut().random::<u64>());
+        }
+    }
+}
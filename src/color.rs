@@ -0,0 +1,383 @@
+//! Random color generation.
+//!
+//! Hues are in degrees, saturation and lightness in `[0.0, 1.0]`, matching
+//! Bevy's [`Hsla`]. Perturbation works in Oklab, where equal distances look
+//! like roughly equal changes, so small amounts stay recognizably the same
+//! color regardless of hue.
+
+use bevy::color::{Color, Hsla, Oklaba, Srgba};
+use rand::distr::uniform::SampleRange;
+use rand::{Rng, RngExt};
+
+use crate::{EntityRng, GlobalRng};
+
+/// The golden ratio conjugate as a fraction of a full hue turn.
+const GOLDEN_ANGLE: f32 = 0.618_034 * 360.0;
+
+/// Hue relationships used by [`GlobalRng::palette`] and
+/// [`EntityRng::palette`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Harmony {
+    /// The base hue and its neighbours 30° either side.
+    Analogous,
+    /// The base hue and the hue opposite it.
+    Complementary,
+    /// Three hues spaced 120° apart.
+    Triadic,
+}
+
+impl Harmony {
+    /// Get the hue offsets in degrees, starting with the base hue.
+    #[must_use]
+    pub fn offsets(self) -> &'static [f32] {
+        match self {
+            Self::Analogous => &[0.0, -30.0, 30.0],
+            Self::Complementary => &[0.0, 180.0],
+            Self::Triadic => &[0.0, 120.0, 240.0],
+        }
+    }
+}
+
+/// An endless sequence of hues that stay far apart from each other.
+///
+/// Each hue is offset from the previous one by the golden angle, so any
+/// number of consecutive hues is spread close to evenly around the color wheel.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GoldenHues {
+    hue: f32,
+}
+
+impl GoldenHues {
+    /// Start the sequence at `hue` degrees.
+    #[must_use]
+    pub fn new(hue: f32) -> Self {
+        Self {
+            hue: hue.rem_euclid(360.0),
+        }
+    }
+}
+
+impl Iterator for GoldenHues {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        let hue = self.hue;
+        self.hue = (self.hue + GOLDEN_ANGLE).rem_euclid(360.0);
+        Some(hue)
+    }
+}
+
+fn color_hsl<R, H, S, L>(rng: &mut R, hue: H, saturation: S, lightness: L) -> Color
+where
+    R: Rng + ?Sized,
+    H: SampleRange<f32>,
+    S: SampleRange<f32>,
+    L: SampleRange<f32>,
+{
+    Color::hsl(
+        rng.random_range(hue).rem_euclid(360.0),
+        rng.random_range(saturation).clamp(0.0, 1.0),
+        rng.random_range(lightness).clamp(0.0, 1.0),
+    )
+}
+
+fn palette<R, S, L>(rng: &mut R, harmony: Harmony, saturation: S, lightness: L) -> Vec<Color>
+where
+    R: Rng + ?Sized,
+    S: SampleRange<f32> + Clone,
+    L: SampleRange<f32> + Clone,
+{
+    let base = rng.random_range(0.0..360.0);
+    harmony
+        .offsets()
+        .iter()
+        .map(|offset| {
+            Color::hsl(
+                (base + offset).rem_euclid(360.0),
+                rng.random_range(saturation.clone()).clamp(0.0, 1.0),
+                rng.random_range(lightness.clone()).clamp(0.0, 1.0),
+            )
+        })
+        .collect()
+}
+
+fn perturb_color<R: Rng + ?Sized>(rng: &mut R, color: Color, amount: f32) -> Color {
+    assert!(
+        amount >= 0.0 && amount.is_finite(),
+        "amount must be non-negative and finite"
+    );
+
+    // Uniform point in a ball of radius `amount`, by rejection from the cube
+    let offset = loop {
+        let [l, a, b] = [(); 3].map(|()| rng.random_range(-1.0..=1.0_f32));
+        if l * l + a * a + b * b <= 1.0 {
+            break [l * amount, a * amount, b * amount];
+        }
+    };
+
+    let lab = Oklaba::from(color);
+    let moved = Oklaba::new(
+        (lab.lightness + offset[0]).clamp(0.0, 1.0),
+        lab.a + offset[1],
+        lab.b + offset[2],
+        lab.alpha,
+    );
+    let srgba = Srgba::from(moved);
+    Color::Srgba(Srgba::new(
+        srgba.red.clamp(0.0, 1.0),
+        srgba.green.clamp(0.0, 1.0),
+        srgba.blue.clamp(0.0, 1.0),
+        srgba.alpha,
+    ))
+}
+
+fn distinct_colors<R: Rng + ?Sized>(
+    rng: &mut R,
+    count: usize,
+    saturation: f32,
+    lightness: f32,
+) -> Vec<Color> {
+    GoldenHues::new(rng.random_range(0.0..360.0))
+        .take(count)
+        .map(|hue| Color::Hsla(Hsla::hsl(hue, saturation, lightness)))
+        .collect()
+}
+
+impl GlobalRng {
+    /// Generate a color with hue, saturation and lightness drawn uniformly
+    /// from the given ranges.
+    ///
+    /// Hue is in degrees and wraps, so `330.0..390.0` covers reds on both sides
+    /// of 0°. Saturation and lightness are clamped to `[0.0, 1.0]`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any range is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use msg_rng::GlobalRng;
+    /// # let mut rng = GlobalRng::seeded(42);
+    /// // Muted greens for foliage
+    /// let leaf = rng.color_hsl(90.0..140.0, 0.3..0.6, 0.25..0.45);
+    /// ```
+    pub fn color_hsl<H, S, L>(&mut self, hue: H, saturation: S, lightness: L) -> Color
+    where
+        H: SampleRange<f32>,
+        S: SampleRange<f32>,
+        L: SampleRange<f32>,
+    {
+        color_hsl(&mut self.rng, hue, saturation, lightness)
+    }
+
+    /// Start a [`GoldenHues`] sequence at a random hue.
+    pub fn golden_hues(&mut self) -> GoldenHues {
+        GoldenHues::new(self.rng.random_range(0.0..360.0))
+    }
+
+    /// Generate `count` visually distinct colors with the same saturation and
+    /// lightness, e.g. for team or faction tints.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use msg_rng::GlobalRng;
+    /// # let mut rng = GlobalRng::seeded(42);
+    /// let factions = rng.distinct_colors(6, 0.7, 0.5);
+    /// assert_eq!(factions.len(), 6);
+    /// ```
+    pub fn distinct_colors(&mut self, count: usize, saturation: f32, lightness: f32) -> Vec<Color> {
+        distinct_colors(&mut self.rng, count, saturation, lightness)
+    }
+
+    /// Generate a palette around a random base hue following `harmony`.
+    ///
+    /// Each color draws its own saturation and lightness from the ranges.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either range is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use msg_rng::GlobalRng;
+    /// use msg_rng::Harmony;
+    /// # let mut rng = GlobalRng::seeded(42);
+    ///
+    /// let [primary, accent] = rng.palette(Harmony::Complementary, 0.6..0.9, 0.4..0.6)[..] else {
+    ///     unreachable!()
+    /// };
+    /// ```
+    pub fn palette<S, L>(&mut self, harmony: Harmony, saturation: S, lightness: L) -> Vec<Color>
+    where
+        S: SampleRange<f32> + Clone,
+        L: SampleRange<f32> + Clone,
+    {
+        palette(&mut self.rng, harmony, saturation, lightness)
+    }
+
+    /// Shift a color to a random point at most `amount` away in Oklab space.
+    ///
+    /// An `amount` around `0.02` is a barely noticeable variation, `0.1` is a
+    /// clearly different shade. The result is clamped to the sRGB gamut and
+    /// keeps the original alpha.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `amount` is negative or not finite.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use bevy::color::Color;
+    /// # use msg_rng::GlobalRng;
+    /// # let mut rng = GlobalRng::seeded(42);
+    ///
+    /// let brick = Color::srgb(0.6, 0.25, 0.2);
+    /// let tint = rng.perturb_color(brick, 0.05);
+    /// ```
+    pub fn perturb_color(&mut self, color: Color, amount: f32) -> Color {
+        perturb_color(&mut self.rng, color, amount)
+    }
+}
+
+impl EntityRng {
+    /// Generate a color with hue, saturation and lightness drawn uniformly
+    /// from the given ranges.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any range is empty.
+    pub fn color_hsl<H, S, L>(&mut self, hue: H, saturation: S, lightness: L) -> Color
+    where
+        H: SampleRange<f32>,
+        S: SampleRange<f32>,
+        L: SampleRange<f32>,
+    {
+        color_hsl(&mut self.rng, hue, saturation, lightness)
+    }
+
+    /// Start a [`GoldenHues`] sequence at a random hue.
+    pub fn golden_hues(&mut self) -> GoldenHues {
+        GoldenHues::new(self.rng.random_range(0.0..360.0))
+    }
+
+    /// Generate `count` visually distinct colors with the same saturation and
+    /// lightness.
+    pub fn distinct_colors(&mut self, count: usize, saturation: f32, lightness: f32) -> Vec<Color> {
+        distinct_colors(&mut self.rng, count, saturation, lightness)
+    }
+
+    /// Generate a palette around a random base hue following `harmony`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either range is empty.
+    pub fn palette<S, L>(&mut self, harmony: Harmony, saturation: S, lightness: L) -> Vec<Color>
+    where
+        S: SampleRange<f32> + Clone,
+        L: SampleRange<f32> + Clone,
+    {
+        palette(&mut self.rng, harmony, saturation, lightness)
+    }
+
+    /// Shift a color to a random point at most `amount` away in Oklab space.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `amount` is negative or not finite.
+    pub fn perturb_color(&mut self, color: Color, amount: f32) -> Color {
+        perturb_color(&mut self.rng, color, amount)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::color::Hue;
+
+    use super::*;
+
+    fn hue_distance(a: f32, b: f32) -> f32 {
+        let d = (a - b).rem_euclid(360.0);
+        d.min(360.0 - d)
+    }
+
+    #[test]
+    fn hsl_colors_stay_in_range() {
+        let mut rng = GlobalRng::seeded(1);
+
+        for _ in 0..1000 {
+            let hsla = Hsla::from(rng.color_hsl(330.0..390.0, 0.2..0.4, 0.5..=0.5));
+            assert!(hue_distance(hsla.hue, 0.0) <= 30.01, "{hsla:?}");
+            assert!((0.2..0.4).contains(&hsla.saturation), "{hsla:?}");
+            assert!((hsla.lightness - 0.5).abs() < 1e-6, "{hsla:?}");
+        }
+    }
+
+    #[test]
+    fn golden_hues_are_well_spread() {
+        let hues: Vec<f32> = GoldenHues::new(10.0).take(8).collect();
+        assert_eq!(hues[0], 10.0);
+
+        for (i, a) in hues.iter().enumerate() {
+            for b in &hues[i + 1..] {
+                assert!(hue_distance(*a, *b) > 25.0, "{hues:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn palettes_follow_harmony() {
+        let mut rng = EntityRng::seeded(2);
+
+        for harmony in [Harmony::Analogous, Harmony::Complementary, Harmony::Triadic] {
+            let colors = rng.palette(harmony, 0.5..0.9, 0.4..0.6);
+            assert_eq!(colors.len(), harmony.offsets().len());
+
+            let base = colors[0].hue();
+            for (color, offset) in colors.iter().zip(harmony.offsets()) {
+                assert!(
+                    hue_distance(color.hue(), base + offset) < 0.1,
+                    "{harmony:?}: {colors:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn perturbation_stays_close_in_oklab() {
+        let mut rng = GlobalRng::seeded(3);
+        let base = Color::srgba(0.4, 0.5, 0.6, 0.8);
+        let lab = Oklaba::from(base);
+
+        for _ in 0..1000 {
+            let moved = Oklaba::from(rng.perturb_color(base, 0.05));
+            let distance = ((moved.lightness - lab.lightness).powi(2)
+                + (moved.a - lab.a).powi(2)
+                + (moved.b - lab.b).powi(2))
+            .sqrt();
+            assert!(distance <= 0.0501, "{distance}");
+            assert!((moved.alpha - 0.8).abs() < 1e-6);
+        }
+
+        let same = rng.perturb_color(base, 0.0).to_srgba();
+        assert!((same.red - 0.4).abs() < 1e-4 && (same.blue - 0.6).abs() < 1e-4);
+    }
+
+    #[test]
+    fn colors_are_deterministic() {
+        let mut rng1 = GlobalRng::seeded(4);
+        let mut rng2 = EntityRng::seeded(4);
+
+        assert_eq!(
+            rng1.distinct_colors(5, 0.7, 0.5),
+            rng2.distinct_colors(5, 0.7, 0.5)
+        );
+        assert_eq!(
+            rng1.color_hsl(0.0..360.0, 0.0..1.0, 0.0..1.0),
+            rng2.color_hsl(0.0..360.0, 0.0..1.0, 0.0..1.0)
+        );
+    }
+}
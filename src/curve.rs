@@ -0,0 +1,335 @@
+//! Sampling from designer-authored curves and density tables.
+//!
+//! A curve is treated as an (unnormalized) probability density over its
+//! domain. [`CurveDistribution`] precomputes its cumulative table once, so
+//! repeated draws only cost a binary search.
+
+use std::fmt;
+
+use bevy::math::curve::Curve;
+use rand::distr::Distribution;
+use rand::{Rng, RngExt};
+
+use crate::{EntityRng, GlobalRng};
+
+/// Number of density samples taken by [`GlobalRng::sample_curve`] and
+/// [`EntityRng::sample_curve`].
+pub const DEFAULT_CURVE_SAMPLES: usize = 256;
+
+/// Error returned when a curve or density table can't be used as a distribution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CurveDistributionError {
+    /// The domain is unbounded or has zero length.
+    InvalidDomain,
+    /// Fewer than two density samples were given.
+    TooFewSamples,
+    /// A density value was negative, NaN or infinite.
+    InvalidDensity,
+    /// The density is zero everywhere.
+    ZeroDensity,
+}
+
+impl fmt::Display for CurveDistributionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidDomain => write!(f, "domain is unbounded or has zero length"),
+            Self::TooFewSamples => write!(f, "at least two density samples are required"),
+            Self::InvalidDensity => write!(f, "density is negative, NaN or infinite"),
+            Self::ZeroDensity => write!(f, "density is zero everywhere"),
+        }
+    }
+}
+
+impl std::error::Error for CurveDistributionError {}
+
+/// A precomputed inverse-CDF table built from a probability density.
+///
+/// The density is linearly interpolated between evenly spaced samples, and
+/// draws invert that piecewise-linear density exactly.
+///
+/// # Examples
+///
+/// ```rust
+/// use bevy::math::curve::{FunctionCurve, Interval};
+/// use msg_rng::prelude::*;
+/// use msg_rng::CurveDistribution;
+///
+/// // Damage falls off linearly from 0 to 10 meters
+/// let falloff = FunctionCurve::new(Interval::new(0.0, 10.0).unwrap(), |d: f32| 10.0 - d);
+/// let distance = CurveDistribution::from_curve(&falloff, 64).unwrap();
+///
+/// let mut rng = GlobalRng::seeded(42);
+/// let hit_at = rng.sample_distribution(&distance);
+/// assert!((0.0..=10.0).contains(&hit_at));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct CurveDistribution {
+    start: f32,
+    end: f32,
+    /// Normalized density at each sample point
+    density: Vec<f64>,
+    /// Cumulative probability at each sample point
+    cdf: Vec<f64>,
+}
+
+impl CurveDistribution {
+    /// Build a distribution by sampling a curve's density at `samples` evenly
+    /// spaced points across its domain.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the domain is unbounded or empty, if `samples < 2`,
+    /// or if the sampled density is invalid or zero everywhere.
+    pub fn from_curve(
+        curve: &impl Curve<f32>,
+        samples: usize,
+    ) -> Result<Self, CurveDistributionError> {
+        let domain = curve.domain();
+        if !domain.is_bounded() || domain.length() <= 0.0 {
+            return Err(CurveDistributionError::InvalidDomain);
+        }
+        if samples < 2 {
+            return Err(CurveDistributionError::TooFewSamples);
+        }
+
+        let density: Vec<f32> = domain
+            .spaced_points(samples)
+            .map_err(|_| CurveDistributionError::InvalidDomain)?
+            .map(|t| curve.sample_unchecked(t))
+            .collect();
+        Self::from_density(domain.start(), domain.end(), &density)
+    }
+
+    /// Build a distribution from density values evenly spaced over
+    /// `[start, end]`, e.g. a table authored in the editor.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the range is not finite or empty, if fewer than two
+    /// values are given, or if the density is invalid or zero everywhere.
+    pub fn from_density(
+        start: f32,
+        end: f32,
+        density: &[f32],
+    ) -> Result<Self, CurveDistributionError> {
+        if !(start.is_finite() && end.is_finite() && start < end) {
+            return Err(CurveDistributionError::InvalidDomain);
+        }
+        if density.len() < 2 {
+            return Err(CurveDistributionError::TooFewSamples);
+        }
+        if density.iter().any(|d| !(*d >= 0.0 && d.is_finite())) {
+            return Err(CurveDistributionError::InvalidDensity);
+        }
+
+        // Integrate with unit spacing, then normalize both tables by the total area
+        let mut cdf = Vec::with_capacity(density.len());
+        let mut area = 0.0;
+        cdf.push(0.0);
+        for pair in density.windows(2) {
+            area += (f64::from(pair[0]) + f64::from(pair[1])) / 2.0;
+            cdf.push(area);
+        }
+        if area <= 0.0 {
+            return Err(CurveDistributionError::ZeroDensity);
+        }
+
+        cdf.iter_mut().for_each(|c| *c /= area);
+        Ok(Self {
+            start,
+            end,
+            density: density.iter().map(|d| f64::from(*d) / area).collect(),
+            cdf,
+        })
+    }
+
+    /// Get the start of the sampled range.
+    #[must_use]
+    pub fn start(&self) -> f32 {
+        self.start
+    }
+
+    /// Get the end of the sampled range.
+    #[must_use]
+    pub fn end(&self) -> f32 {
+        self.end
+    }
+
+    /// Map a uniform value in `[0.0, 1.0)` to a value distributed by this table.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+    pub fn quantile(&self, u: f64) -> f32 {
+        let u = u.clamp(0.0, 1.0);
+        let segments = self.cdf.len() - 1;
+        // First point whose CDF exceeds `u`; zero-mass segments are skipped
+        let segment = self.cdf.partition_point(|&c| c <= u).clamp(1, segments) - 1;
+
+        let a = self.density[segment];
+        let b = self.density[segment + 1];
+        let remaining = u - self.cdf[segment];
+        // Solve a·x + (b - a)·x²/2 = remaining for x in [0, 1], written in a
+        // form that stays stable when the density is flat or starts at zero
+        let offset = {
+            let disc = (a * a + 2.0 * (b - a) * remaining).max(0.0);
+            let denom = a + disc.sqrt();
+            if denom > 0.0 {
+                (2.0 * remaining / denom).clamp(0.0, 1.0)
+            } else {
+                0.0
+            }
+        };
+
+        let t = (segment as f64 + offset) / segments as f64;
+        (f64::from(self.start) + t * f64::from(self.end - self.start)) as f32
+    }
+}
+
+impl Distribution<f32> for CurveDistribution {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f32 {
+        self.quantile(rng.random::<f64>())
+    }
+}
+
+fn sample_curve<R: Rng + ?Sized>(
+    rng: &mut R,
+    curve: &impl Curve<f32>,
+) -> Result<f32, CurveDistributionError> {
+    CurveDistribution::from_curve(curve, DEFAULT_CURVE_SAMPLES).map(|dist| dist.sample(rng))
+}
+
+impl GlobalRng {
+    /// Sample a value from any `rand` distribution, including
+    /// [`CurveDistribution`] tables.
+    pub fn sample_distribution<T, D: Distribution<T>>(&mut self, distribution: &D) -> T {
+        distribution.sample(&mut self.rng)
+    }
+
+    /// Sample a value from a curve's domain, treating the curve as a
+    /// probability density.
+    ///
+    /// This builds a fresh table of [`DEFAULT_CURVE_SAMPLES`] points on each
+    /// call; build a [`CurveDistribution`] once when sampling repeatedly.
+    ///
+    /// # Errors
+    ///
+    /// See [`CurveDistribution::from_curve`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use bevy::math::curve::{FunctionCurve, Interval};
+    /// # use msg_rng::GlobalRng;
+    /// # let mut rng = GlobalRng::seeded(42);
+    ///
+    /// let bell = FunctionCurve::new(Interval::new(-1.0, 1.0).unwrap(), |x: f32| 1.0 - x * x);
+    /// let offset = rng.sample_curve(&bell).unwrap();
+    /// ```
+    pub fn sample_curve(&mut self, curve: &impl Curve<f32>) -> Result<f32, CurveDistributionError> {
+        sample_curve(&mut self.rng, curve)
+    }
+}
+
+impl EntityRng {
+    /// Sample a value from any `rand` distribution.
+    pub fn sample_distribution<T, D: Distribution<T>>(&mut self, distribution: &D) -> T {
+        distribution.sample(&mut self.rng)
+    }
+
+    /// Sample a value from a curve's domain, treating the curve as a
+    /// probability density.
+    ///
+    /// # Errors
+    ///
+    /// See [`CurveDistribution::from_curve`].
+    pub fn sample_curve(&mut self, curve: &impl Curve<f32>) -> Result<f32, CurveDistributionError> {
+        sample_curve(&mut self.rng, curve)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::math::curve::{FunctionCurve, Interval};
+
+    use super::*;
+
+    fn mean(samples: impl Iterator<Item = f32>) -> f32 {
+        let (sum, count) = samples.fold((0.0, 0.0), |(sum, count), x| (sum + x, count + 1.0));
+        sum / count
+    }
+
+    #[test]
+    fn flat_density_is_uniform() {
+        let dist = CurveDistribution::from_density(2.0, 4.0, &[1.0, 1.0, 1.0]).unwrap();
+        let mut rng = GlobalRng::seeded(1);
+
+        let avg = mean((0..20_000).map(|_| rng.sample_distribution(&dist)));
+        assert!((avg - 3.0).abs() < 0.02, "mean {avg}");
+        assert!((dist.quantile(0.25) - 2.5).abs() < 1e-5);
+    }
+
+    #[test]
+    fn ramp_curve_matches_expected_mean() {
+        // f(x) = 2x on [0, 1] has mean 2/3
+        let ramp = FunctionCurve::new(Interval::UNIT, |x: f32| 2.0 * x);
+        let mut rng = EntityRng::seeded(2);
+
+        let dist = CurveDistribution::from_curve(&ramp, 8).unwrap();
+        let avg = mean((0..20_000).map(|_| rng.sample_distribution(&dist)));
+        assert!((avg - 2.0 / 3.0).abs() < 0.01, "mean {avg}");
+
+        // Linear density is inverted exactly: F(x) = x², so x = sqrt(u)
+        assert!((dist.quantile(0.49) - 0.7).abs() < 1e-5);
+    }
+
+    #[test]
+    fn zero_regions_are_never_sampled() {
+        let dist = CurveDistribution::from_density(0.0, 4.0, &[0.0, 0.0, 1.0, 1.0, 0.0]).unwrap();
+        let mut rng = GlobalRng::seeded(3);
+
+        for _ in 0..5000 {
+            let x = rng.sample_distribution(&dist);
+            assert!((1.0..=4.0).contains(&x), "{x}");
+        }
+    }
+
+    #[test]
+    fn invalid_curves_are_rejected() {
+        let mut rng = GlobalRng::seeded(4);
+
+        let unbounded = FunctionCurve::new(Interval::EVERYWHERE, |_: f32| 1.0);
+        assert_eq!(
+            rng.sample_curve(&unbounded),
+            Err(CurveDistributionError::InvalidDomain)
+        );
+
+        let flat_zero = FunctionCurve::new(Interval::UNIT, |_: f32| 0.0);
+        assert_eq!(
+            rng.sample_curve(&flat_zero),
+            Err(CurveDistributionError::ZeroDensity)
+        );
+
+        assert_eq!(
+            CurveDistribution::from_density(0.0, 1.0, &[1.0, -1.0]),
+            Err(CurveDistributionError::InvalidDensity)
+        );
+        assert_eq!(
+            CurveDistribution::from_density(0.0, 1.0, &[1.0]),
+            Err(CurveDistributionError::TooFewSamples)
+        );
+    }
+
+    #[test]
+    fn curve_sampling_is_deterministic() {
+        let curve = FunctionCurve::new(Interval::new(0.0, 5.0).unwrap(), |x: f32| x.sin().abs());
+        let mut rng1 = GlobalRng::seeded(5);
+        let mut rng2 = EntityRng::seeded(5);
+
+        let a: Vec<f32> = (0..10)
+            .map(|_| rng1.sample_curve(&curve).unwrap())
+            .collect();
+        let b: Vec<f32> = (0..10)
+            .map(|_| rng2.sample_curve(&curve).unwrap())
+            .collect();
+        assert_eq!(a, b);
+    }
+}
@@ -0,0 +1,596 @@
+//! Statistical distributions for [`GlobalRng`] and [`EntityRng`].
+//!
+//! Continuous distributions return `f64`; discrete ones return counts as `u64`
+//! and stay efficient for large parameters.
+//!
+//! Sampling is backed by `rand_distr`. Results are reproducible from the seed
+//! on a given platform, but functions like `exp` and `ln` come from the
+//! platform's math library, so values may differ in the last bits between
+//! platforms. Don't rely on bit-exact samples across targets, e.g. for
+//! lockstep networking.
+
+use rand::{Rng, RngExt};
+use rand_distr::{
+    Beta, Binomial, Distribution, Exp, Gamma, Geometric, LogNormal, Normal, Poisson, Triangular,
+};
+
+use crate::{EntityRng, GlobalRng};
+
+pub(crate) fn normal<R: Rng + ?Sized>(rng: &mut R, mean: f64, std_dev: f64) -> f64 {
+    Normal::new(mean, std_dev)
+        .unwrap_or_else(|err| panic!("invalid normal distribution: {err}"))
+        .sample(rng)
+}
+
+fn log_normal<R: Rng + ?Sized>(rng: &mut R, mu: f64, sigma: f64) -> f64 {
+    LogNormal::new(mu, sigma)
+        .unwrap_or_else(|err| panic!("invalid log-normal distribution: {err}"))
+        .sample(rng)
+}
+
+pub(crate) fn exponential<R: Rng + ?Sized>(rng: &mut R, lambda: f64) -> f64 {
+    Exp::new(lambda)
+        .unwrap_or_else(|err| panic!("invalid exponential distribution: {err}"))
+        .sample(rng)
+}
+
+fn triangular<R: Rng + ?Sized>(rng: &mut R, min: f64, mode: f64, max: f64) -> f64 {
+    Triangular::new(min, max, mode)
+        .unwrap_or_else(|err| panic!("invalid triangular distribution: {err}"))
+        .sample(rng)
+}
+
+fn beta<R: Rng + ?Sized>(rng: &mut R, alpha: f64, beta: f64) -> f64 {
+    Beta::new(alpha, beta)
+        .unwrap_or_else(|err| panic!("invalid beta distribution: {err}"))
+        .sample(rng)
+}
+
+fn gamma<R: Rng + ?Sized>(rng: &mut R, shape: f64, scale: f64) -> f64 {
+    Gamma::new(shape, scale)
+        .unwrap_or_else(|err| panic!("invalid gamma distribution: {err}"))
+        .sample(rng)
+}
+
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn poisson<R: Rng + ?Sized>(rng: &mut R, lambda: f64) -> u64 {
+    if lambda == 0.0 {
+        return 0;
+    }
+    // Poisson samples are whole numbers well below `u64::MAX` for any allowed lambda
+    Poisson::new(lambda)
+        .unwrap_or_else(|err| panic!("invalid Poisson distribution: {err}"))
+        .sample(rng) as u64
+}
+
+fn binomial<R: Rng + ?Sized>(rng: &mut R, trials: u64, probability: f64) -> u64 {
+    Binomial::new(trials, probability)
+        .unwrap_or_else(|err| panic!("invalid binomial distribution: {err}"))
+        .sample(rng)
+}
+
+fn geometric<R: Rng + ?Sized>(rng: &mut R, probability: f64) -> u64 {
+    Geometric::new(probability)
+        .unwrap_or_else(|err| panic!("invalid geometric distribution: {err}"))
+        .sample(rng)
+}
+
+/// Sample the number of failures before `successes` successes.
+///
+/// Drawn as a gamma-Poisson mixture, which costs the same regardless of how
+/// many successes are requested. Mixed means beyond what [`Poisson`] accepts
+/// are already near `u64::MAX`, so those use a normal approximation instead.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn negative_binomial<R: Rng + ?Sized>(rng: &mut R, successes: u32, probability: f64) -> u64 {
+    assert!(
+        probability > 0.0 && probability <= 1.0,
+        "invalid negative binomial distribution: probability must be in (0, 1]"
+    );
+    if successes == 0 || probability == 1.0 {
+        return 0;
+    }
+
+    let lambda = gamma(rng, f64::from(successes), (1.0 - probability) / probability);
+    if lambda <= Poisson::<f64>::MAX_LAMBDA {
+        poisson(rng, lambda)
+    } else if lambda.is_finite() {
+        // Float to int casts saturate, so this tops out at `u64::MAX`
+        normal(rng, lambda, lambda.sqrt()).round() as u64
+    } else {
+        u64::MAX
+    }
+}
+
+/// Sample a normal distribution restricted to `[min, max]`.
+///
+/// Uses Robert's (1995) rejection scheme, which picks a normal, uniform or
+/// exponential proposal depending on where the interval sits, so tails far
+/// from the mean stay cheap instead of looping on plain rejection.
+fn truncated_normal<R: Rng + ?Sized>(
+    rng: &mut R,
+    mean: f64,
+    std_dev: f64,
+    min: f64,
+    max: f64,
+) -> f64 {
+    assert!(
+        std_dev > 0.0 && std_dev.is_finite(),
+        "invalid truncated normal distribution: std_dev must be positive and finite"
+    );
+    assert!(
+        min <= max,
+        "invalid truncated normal distribution: min must not exceed max"
+    );
+
+    let a = (min - mean) / std_dev;
+    let b = (max - mean) / std_dev;
+    if a == b {
+        return min;
+    }
+
+    let z = if b < 0.0 {
+        -standard_tail(rng, -b, -a)
+    } else if a > 0.0 {
+        standard_tail(rng, a, b)
+    } else if b - a >= (2.0 * std::f64::consts::PI).sqrt() {
+        loop {
+            let z: f64 = rng.sample(rand_distr::StandardNormal);
+            if (a..=b).contains(&z) {
+                break z;
+            }
+        }
+    } else {
+        loop {
+            let z = rng.random_range(a..=b);
+            if rng.random::<f64>() <= (-z * z / 2.0).exp() {
+                break z;
+            }
+        }
+    };
+
+    (mean + z * std_dev).clamp(min, max)
+}
+
+/// Sample a standard normal restricted to `[a, b]` with `0 < a < b`.
+fn standard_tail<R: Rng + ?Sized>(rng: &mut R, a: f64, b: f64) -> f64 {
+    let alpha = f64::midpoint(a, (a * a + 4.0).sqrt());
+    let uniform_limit = 2.0 * std::f64::consts::E.sqrt() / (a + (a * a + 4.0).sqrt())
+        * ((a * a - a * (a * a + 4.0).sqrt()) / 4.0).exp();
+
+    if b - a < uniform_limit {
+        loop {
+            let z = rng.random_range(a..=b);
+            if rng.random::<f64>() <= ((a * a - z * z) / 2.0).exp() {
+                break z;
+            }
+        }
+    } else {
+        loop {
+            // `1 - u` keeps the logarithm finite since `u` lies in [0, 1)
+            let z = a - (-rng.random::<f64>()).ln_1p() / alpha;
+            if z <= b && rng.random::<f64>() <= (-(z - alpha).powi(2) / 2.0).exp() {
+                break z;
+            }
+        }
+    }
+}
+
+impl GlobalRng {
+    /// Sample a normal (Gaussian) distribution.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `std_dev` is negative or not finite.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use msg_rng::GlobalRng;
+    /// # let mut rng = GlobalRng::seeded(42);
+    /// let reaction_time = rng.normal(0.25, 0.05);
+    /// ```
+    pub fn normal(&mut self, mean: f64, std_dev: f64) -> f64 {
+        normal(&mut self.rng, mean, std_dev)
+    }
+
+    /// Sample a log-normal distribution, where `ln(x)` is normal with mean
+    /// `mu` and standard deviation `sigma`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `sigma` is negative or not finite.
+    pub fn log_normal(&mut self, mu: f64, sigma: f64) -> f64 {
+        log_normal(&mut self.rng, mu, sigma)
+    }
+
+    /// Sample an exponential distribution with rate `lambda` (mean `1 / lambda`).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `lambda` is negative or NaN.
+    pub fn exponential(&mut self, lambda: f64) -> f64 {
+        exponential(&mut self.rng, lambda)
+    }
+
+    /// Sample a triangular distribution over `[min, max]` peaking at `mode`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `mode` is not within `[min, max]`.
+    pub fn triangular(&mut self, min: f64, mode: f64, max: f64) -> f64 {
+        triangular(&mut self.rng, min, mode, max)
+    }
+
+    /// Sample a beta distribution on `[0.0, 1.0]`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `alpha` or `beta` is not positive.
+    pub fn beta(&mut self, alpha: f64, beta: f64) -> f64 {
+        self::beta(&mut self.rng, alpha, beta)
+    }
+
+    /// Sample a gamma distribution with the given shape and scale.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `shape` or `scale` is not positive.
+    pub fn gamma(&mut self, shape: f64, scale: f64) -> f64 {
+        gamma(&mut self.rng, shape, scale)
+    }
+
+    /// Sample a normal distribution restricted to `[min, max]`.
+    ///
+    /// Unlike clamping, values never pile up on the bounds.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `std_dev` is not positive and finite, or if `min > max`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use msg_rng::GlobalRng;
+    /// # let mut rng = GlobalRng::seeded(42);
+    /// let damage = rng.truncated_normal(50.0, 10.0, 30.0, 70.0);
+    /// assert!((30.0..=70.0).contains(&damage));
+    /// ```
+    pub fn truncated_normal(&mut self, mean: f64, std_dev: f64, min: f64, max: f64) -> f64 {
+        truncated_normal(&mut self.rng, mean, std_dev, min, max)
+    }
+
+    /// Sample a Poisson distribution with mean `lambda`.
+    ///
+    /// Useful for "how many events happen this tick" style counts.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `lambda` is negative, not finite, or above `Poisson::MAX_LAMBDA`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use msg_rng::GlobalRng;
+    /// # let mut rng = GlobalRng::seeded(42);
+    /// // On average 2.5 spawns per tick
+    /// let spawns = rng.poisson(2.5);
+    /// ```
+    pub fn poisson(&mut self, lambda: f64) -> u64 {
+        poisson(&mut self.rng, lambda)
+    }
+
+    /// Sample the number of successes in `trials` independent attempts that
+    /// each succeed with `probability`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `probability` is not within `[0.0, 1.0]`.
+    pub fn binomial(&mut self, trials: u64, probability: f64) -> u64 {
+        binomial(&mut self.rng, trials, probability)
+    }
+
+    /// Sample the number of failures before the first success, where each
+    /// attempt succeeds with `probability`.
+    ///
+    /// Add one to get the number of attempts including the success.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `probability` is not within `[0.0, 1.0]`.
+    pub fn geometric(&mut self, probability: f64) -> u64 {
+        geometric(&mut self.rng, probability)
+    }
+
+    /// Sample the number of failures before `successes` successes, where each
+    /// attempt succeeds with `probability`.
+    ///
+    /// Counts too large for a Poisson sample use a normal approximation and
+    /// saturate at `u64::MAX` rather than panicking.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `probability` is not within `(0.0, 1.0]`.
+    pub fn negative_binomial(&mut self, successes: u32, probability: f64) -> u64 {
+        negative_binomial(&mut self.rng, successes, probability)
+    }
+}
+
+impl EntityRng {
+    /// Sample a normal (Gaussian) distribution.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `std_dev` is negative or not finite.
+    pub fn normal(&mut self, mean: f64, std_dev: f64) -> f64 {
+        normal(&mut self.rng, mean, std_dev)
+    }
+
+    /// Sample a log-normal distribution.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `sigma` is negative or not finite.
+    pub fn log_normal(&mut self, mu: f64, sigma: f64) -> f64 {
+        log_normal(&mut self.rng, mu, sigma)
+    }
+
+    /// Sample an exponential distribution with rate `lambda`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `lambda` is negative or NaN.
+    pub fn exponential(&mut self, lambda: f64) -> f64 {
+        exponential(&mut self.rng, lambda)
+    }
+
+    /// Sample a triangular distribution over `[min, max]` peaking at `mode`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `mode` is not within `[min, max]`.
+    pub fn triangular(&mut self, min: f64, mode: f64, max: f64) -> f64 {
+        triangular(&mut self.rng, min, mode, max)
+    }
+
+    /// Sample a beta distribution on `[0.0, 1.0]`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `alpha` or `beta` is not positive.
+    pub fn beta(&mut self, alpha: f64, beta: f64) -> f64 {
+        self::beta(&mut self.rng, alpha, beta)
+    }
+
+    /// Sample a gamma distribution with the given shape and scale.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `shape` or `scale` is not positive.
+    pub fn gamma(&mut self, shape: f64, scale: f64) -> f64 {
+        gamma(&mut self.rng, shape, scale)
+    }
+
+    /// Sample a normal distribution restricted to `[min, max]`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `std_dev` is not positive and finite, or if `min > max`.
+    pub fn truncated_normal(&mut self, mean: f64, std_dev: f64, min: f64, max: f64) -> f64 {
+        truncated_normal(&mut self.rng, mean, std_dev, min, max)
+    }
+
+    /// Sample a Poisson distribution with mean `lambda`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `lambda` is negative, not finite, or above `Poisson::MAX_LAMBDA`.
+    pub fn poisson(&mut self, lambda: f64) -> u64 {
+        poisson(&mut self.rng, lambda)
+    }
+
+    /// Sample the number of successes in `trials` attempts.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `probability` is not within `[0.0, 1.0]`.
+    pub fn binomial(&mut self, trials: u64, probability: f64) -> u64 {
+        binomial(&mut self.rng, trials, probability)
+    }
+
+    /// Sample the number of failures before the first success.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `probability` is not within `[0.0, 1.0]`.
+    pub fn geometric(&mut self, probability: f64) -> u64 {
+        geometric(&mut self.rng, probability)
+    }
+
+    /// Sample the number of failures before `successes` successes.
+    ///
+    /// Counts too large for a Poisson sample use a normal approximation and
+    /// saturate at `u64::MAX` rather than panicking.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `probability` is not within `(0.0, 1.0]`.
+    pub fn negative_binomial(&mut self, successes: u32, probability: f64) -> u64 {
+        negative_binomial(&mut self.rng, successes, probability)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLES: usize = 20_000;
+
+    #[allow(clippy::cast_precision_loss)]
+    fn mean_and_variance(samples: &[f64]) -> (f64, f64) {
+        let n = samples.len() as f64;
+        let mean = samples.iter().sum::<f64>() / n;
+        let variance = samples.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / n;
+        (mean, variance)
+    }
+
+    fn collect(mut f: impl FnMut() -> f64) -> Vec<f64> {
+        (0..SAMPLES).map(|_| f()).collect()
+    }
+
+    #[test]
+    fn normal_matches_moments() {
+        let mut rng = GlobalRng::seeded(1);
+        let (mean, variance) = mean_and_variance(&collect(|| rng.normal(10.0, 2.0)));
+        assert!((mean - 10.0).abs() < 0.1, "mean {mean}");
+        assert!((variance - 4.0).abs() < 0.2, "variance {variance}");
+    }
+
+    #[test]
+    fn exponential_matches_moments() {
+        let mut rng = GlobalRng::seeded(2);
+        let (mean, variance) = mean_and_variance(&collect(|| rng.exponential(0.5)));
+        assert!((mean - 2.0).abs() < 0.1, "mean {mean}");
+        assert!((variance - 4.0).abs() < 0.4, "variance {variance}");
+    }
+
+    #[test]
+    fn triangular_matches_moments() {
+        let mut rng = GlobalRng::seeded(3);
+        let samples = collect(|| rng.triangular(0.0, 3.0, 6.0));
+        let (mean, _) = mean_and_variance(&samples);
+        assert!((mean - 3.0).abs() < 0.05, "mean {mean}");
+        assert!(samples.iter().all(|x| (0.0..=6.0).contains(x)));
+    }
+
+    #[test]
+    fn beta_and_gamma_match_means() {
+        let mut rng = EntityRng::seeded(4);
+        let (beta_mean, _) = mean_and_variance(&collect(|| rng.beta(2.0, 6.0)));
+        assert!((beta_mean - 0.25).abs() < 0.01, "beta mean {beta_mean}");
+
+        let (gamma_mean, gamma_variance) = mean_and_variance(&collect(|| rng.gamma(3.0, 2.0)));
+        assert!((gamma_mean - 6.0).abs() < 0.15, "gamma mean {gamma_mean}");
+        assert!(
+            (gamma_variance - 12.0).abs() < 1.0,
+            "gamma variance {gamma_variance}"
+        );
+    }
+
+    #[test]
+    fn log_normal_matches_median() {
+        let mut rng = GlobalRng::seeded(5);
+        let mut samples = collect(|| rng.log_normal(1.0, 0.5));
+        samples.sort_by(f64::total_cmp);
+        let median = samples[SAMPLES / 2];
+        assert!((median - 1f64.exp()).abs() < 0.05, "median {median}");
+    }
+
+    #[test]
+    fn truncated_normal_stays_in_bounds() {
+        let mut rng = GlobalRng::seeded(6);
+
+        // Interval around the mean
+        let centered = collect(|| rng.truncated_normal(0.0, 1.0, -1.0, 1.0));
+        assert!(centered.iter().all(|x| (-1.0..=1.0).contains(x)));
+        let (mean, _) = mean_and_variance(&centered);
+        assert!(mean.abs() < 0.02, "centered mean {mean}");
+
+        // Far tails on either side, which plain rejection would struggle with
+        let right = collect(|| rng.truncated_normal(0.0, 1.0, 5.0, 8.0));
+        assert!(right.iter().all(|x| (5.0..=8.0).contains(x)));
+        let left = collect(|| rng.truncated_normal(0.0, 1.0, -5.1, -5.0));
+        assert!(left.iter().all(|x| (-5.1..=-5.0).contains(x)));
+
+        assert!((rng.truncated_normal(0.0, 1.0, 2.0, 2.0) - 2.0).abs() < f64::EPSILON);
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn collect_counts(mut f: impl FnMut() -> u64) -> Vec<f64> {
+        (0..SAMPLES).map(|_| f() as f64).collect()
+    }
+
+    #[test]
+    fn poisson_matches_moments() {
+        let mut rng = GlobalRng::seeded(8);
+        let (mean, variance) = mean_and_variance(&collect_counts(|| rng.poisson(4.0)));
+        assert!((mean - 4.0).abs() < 0.1, "mean {mean}");
+        assert!((variance - 4.0).abs() < 0.3, "variance {variance}");
+        assert_eq!(rng.poisson(0.0), 0);
+    }
+
+    #[test]
+    fn binomial_matches_moments() {
+        let mut rng = EntityRng::seeded(9);
+        let (mean, variance) = mean_and_variance(&collect_counts(|| rng.binomial(20, 0.3)));
+        assert!((mean - 6.0).abs() < 0.1, "mean {mean}");
+        assert!((variance - 4.2).abs() < 0.3, "variance {variance}");
+    }
+
+    #[test]
+    fn geometric_and_negative_binomial_match_means() {
+        let mut rng = GlobalRng::seeded(10);
+        let (geo_mean, _) = mean_and_variance(&collect_counts(|| rng.geometric(0.25)));
+        assert!((geo_mean - 3.0).abs() < 0.15, "geometric mean {geo_mean}");
+
+        let (nb_mean, nb_variance) =
+            mean_and_variance(&collect_counts(|| rng.negative_binomial(5, 0.5)));
+        assert!(
+            (nb_mean - 5.0).abs() < 0.15,
+            "negative binomial mean {nb_mean}"
+        );
+        assert!(
+            (nb_variance - 10.0).abs() < 0.8,
+            "negative binomial variance {nb_variance}"
+        );
+    }
+
+    #[test]
+    fn discrete_distributions_handle_large_parameters() {
+        let mut rng = GlobalRng::seeded(11);
+
+        let hits = rng.binomial(1_000_000_000, 0.5);
+        assert!(hits.abs_diff(500_000_000) < 200_000, "hits {hits}");
+
+        let events = rng.poisson(1e12);
+        assert!(
+            events.abs_diff(1_000_000_000_000) < 10_000_000,
+            "events {events}"
+        );
+
+        let failures = rng.negative_binomial(1_000_000, 0.5);
+        assert!(failures.abs_diff(1_000_000) < 20_000, "failures {failures}");
+
+        let failures = rng.negative_binomial(1000, 1e-6);
+        assert!(
+            failures.abs_diff(999_999_000) < 100_000_000,
+            "failures {failures}"
+        );
+    }
+
+    #[test]
+    fn negative_binomial_saturates_beyond_poisson_range() {
+        let mut rng = EntityRng::seeded(12);
+
+        // Both means are far past `Poisson::MAX_LAMBDA`
+        assert_eq!(rng.negative_binomial(u32::MAX, 1e-12), u64::MAX);
+        assert_eq!(rng.negative_binomial(1, f64::MIN_POSITIVE), u64::MAX);
+    }
+
+    #[test]
+    fn distributions_are_deterministic() {
+        let mut rng1 = GlobalRng::seeded(7);
+        let mut rng2 = EntityRng::seeded(7);
+
+        let a: Vec<f64> = (0..10)
+            .map(|_| rng1.normal(0.0, 1.0) + rng1.gamma(2.0, 1.0))
+            .collect();
+        let b: Vec<f64> = (0..10)
+            .map(|_| rng2.normal(0.0, 1.0) + rng2.gamma(2.0, 1.0))
+            .collect();
+        assert_eq!(a, b);
+
+        let counts1: Vec<u64> = (0..10).map(|_| rng1.poisson(50.0)).collect();
+        let counts2: Vec<u64> = (0..10).map(|_| rng2.poisson(50.0)).collect();
+        assert_eq!(counts1, counts2);
+    }
+}
@@ -0,0 +1,636 @@
+//! Tracery-style procedural text grammars.
+//!
+//! A [`Grammar`] maps symbols to weighted lists of expansions. Expansion text
+//! can reference other symbols with `#symbol#`, transform them with modifiers
+//! like `#symbol.capitalize#` or `#symbol.a#`, and save results for reuse with
+//! actions like `[hero:#name#]`.
+//!
+//! With the `serde` feature a grammar (de)serializes as a plain map of symbol
+//! to expansions, which matches Tracery's JSON format. The `asset` feature
+//! adds [`GrammarPlugin`] to load `.grammar.ron` and `.grammar.json` files.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+#[cfg(feature = "asset")]
+use bevy::prelude::*;
+use rand::rngs::StdRng;
+
+use crate::names::capitalize;
+use crate::weighted::choose_weighted;
+use crate::{RngSource, WeightedError};
+
+/// Maximum nesting of symbol expansions before giving up.
+const MAX_DEPTH: usize = 64;
+
+/// A custom text modifier, applied with `#symbol.name#`.
+pub type Modifier = fn(&str) -> String;
+
+/// One possible expansion of a symbol.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(untagged)
+)]
+pub enum Expansion {
+    /// Expansion text with weight 1.
+    Text(String),
+    /// Expansion text with an explicit weight.
+    Weighted(String, f64),
+}
+
+impl Expansion {
+    fn text(&self) -> &str {
+        match self {
+            Self::Text(text) | Self::Weighted(text, _) => text,
+        }
+    }
+
+    fn weight(&self) -> f64 {
+        match self {
+            Self::Text(_) => 1.0,
+            Self::Weighted(_, weight) => *weight,
+        }
+    }
+}
+
+impl From<&str> for Expansion {
+    fn from(text: &str) -> Self {
+        Self::Text(text.to_owned())
+    }
+}
+
+impl From<String> for Expansion {
+    fn from(text: String) -> Self {
+        Self::Text(text)
+    }
+}
+
+impl From<(&str, f64)> for Expansion {
+    fn from((text, weight): (&str, f64)) -> Self {
+        Self::Weighted(text.to_owned(), weight)
+    }
+}
+
+/// Error returned when a grammar can't be expanded.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GrammarError {
+    /// A tag referenced a symbol that is neither a rule nor a saved variable.
+    UnknownSymbol(String),
+    /// A tag used a modifier that doesn't exist.
+    UnknownModifier(String),
+    /// A `#` tag or `[` action was not closed.
+    Unclosed(String),
+    /// Symbols referenced each other too deeply, usually a rule that always
+    /// expands to itself.
+    RecursionLimit,
+    /// A rule's expansions could not be chosen from.
+    Rule(String, WeightedError),
+}
+
+impl fmt::Display for GrammarError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownSymbol(symbol) => write!(f, "unknown symbol `{symbol}`"),
+            Self::UnknownModifier(modifier) => write!(f, "unknown modifier `{modifier}`"),
+            Self::Unclosed(text) => write!(f, "unclosed tag or action in `{text}`"),
+            Self::RecursionLimit => write!(f, "expansion nested deeper than {MAX_DEPTH} levels"),
+            Self::Rule(symbol, err) => write!(f, "cannot expand `{symbol}`: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for GrammarError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Rule(_, err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+/// A set of symbols with weighted expansions.
+///
+/// Built-in modifiers are `capitalize`, `capitalizeAll`, `a` (a/an), `s`
+/// (plural), `ed` (past tense) and `inQuotes`. Custom ones can be added with
+/// [`Grammar::with_modifier`].
+///
+/// # Examples
+///
+/// ```rust
+/// use msg_rng::prelude::*;
+/// use msg_rng::Grammar;
+///
+/// let grammar = Grammar::new()
+///     .with_rule("origin", ["[hero:#name#]#hero.capitalize# found #item.a#. #hero# was pleased."])
+///     .with_rule("name", ["aria", "bram", "corin"])
+///     .with_rule("item", [("sword", 3.0), ("amulet", 1.0)]);
+///
+/// let mut rng = GlobalRng::seeded(42);
+/// let text = grammar.generate("origin", &mut rng).unwrap();
+/// ```
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+#[cfg_attr(feature = "asset", derive(Asset, TypePath))]
+pub struct Grammar {
+    rules: BTreeMap<String, Vec<Expansion>>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    modifiers: HashMap<String, Modifier>,
+}
+
+impl Grammar {
+    /// Create an empty grammar.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add or replace a rule.
+    #[must_use]
+    pub fn with_rule<I, E>(mut self, symbol: impl Into<String>, expansions: I) -> Self
+    where
+        I: IntoIterator<Item = E>,
+        E: Into<Expansion>,
+    {
+        self.insert_rule(symbol, expansions);
+        self
+    }
+
+    /// Add or override a modifier.
+    #[must_use]
+    pub fn with_modifier(mut self, name: impl Into<String>, modifier: Modifier) -> Self {
+        self.modifiers.insert(name.into(), modifier);
+        self
+    }
+
+    /// Add or replace a rule in place.
+    pub fn insert_rule<I, E>(&mut self, symbol: impl Into<String>, expansions: I)
+    where
+        I: IntoIterator<Item = E>,
+        E: Into<Expansion>,
+    {
+        self.rules.insert(
+            symbol.into(),
+            expansions.into_iter().map(Into::into).collect(),
+        );
+    }
+
+    /// Get the expansions of a rule.
+    #[must_use]
+    pub fn rule(&self, symbol: &str) -> Option<&[Expansion]> {
+        self.rules.get(symbol).map(Vec::as_slice)
+    }
+
+    /// Expand `#symbol#`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if expansion references unknown symbols or modifiers,
+    /// has unclosed tags, recurses too deeply, or hits a rule with no valid
+    /// expansions.
+    pub fn generate<R: RngSource + ?Sized>(
+        &self,
+        symbol: &str,
+        rng: &mut R,
+    ) -> Result<String, GrammarError> {
+        self.expand(&format!("#{symbol}#"), rng)
+    }
+
+    /// Expand every tag and action in `text`.
+    ///
+    /// # Errors
+    ///
+    /// See [`Grammar::generate`].
+    pub fn expand<R: RngSource + ?Sized>(
+        &self,
+        text: &str,
+        rng: &mut R,
+    ) -> Result<String, GrammarError> {
+        Expander {
+            grammar: self,
+            rng: rng.rng_mut(),
+            variables: HashMap::new(),
+        }
+        .expand(text, 0)
+    }
+}
+
+struct Expander<'g, 'r> {
+    grammar: &'g Grammar,
+    rng: &'r mut StdRng,
+    variables: HashMap<String, Vec<String>>,
+}
+
+impl Expander<'_, '_> {
+    fn expand(&mut self, text: &str, depth: usize) -> Result<String, GrammarError> {
+        if depth > MAX_DEPTH {
+            return Err(GrammarError::RecursionLimit);
+        }
+
+        let mut out = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(c) = rest.chars().next() {
+            match c {
+                '\\' => {
+                    let mut chars = rest[1..].chars();
+                    if let Some(escaped) = chars.next() {
+                        out.push(escaped);
+                    }
+                    rest = chars.as_str();
+                }
+                '[' => {
+                    let end = closing(rest, ']').ok_or_else(|| unclosed(text))?;
+                    self.action(&rest[1..end], depth)?;
+                    rest = &rest[end + 1..];
+                }
+                '#' => {
+                    let end = closing(rest, '#').ok_or_else(|| unclosed(text))?;
+                    let expanded = self.tag(&rest[1..end], depth)?;
+                    out.push_str(&expanded);
+                    rest = &rest[end + 1..];
+                }
+                _ => {
+                    out.push(c);
+                    rest = &rest[c.len_utf8()..];
+                }
+            }
+        }
+        Ok(out)
+    }
+
+    /// Run a `name:rule` action, or `name:POP` to restore the previous value.
+    fn action(&mut self, action: &str, depth: usize) -> Result<(), GrammarError> {
+        let Some((name, rule)) = action.split_once(':') else {
+            // Bare actions like `[#setup#]` are expanded for their side effects
+            self.expand(action, depth + 1)?;
+            return Ok(());
+        };
+
+        if rule == "POP" {
+            if let Some(stack) = self.variables.get_mut(name) {
+                stack.pop();
+            }
+        } else {
+            let value = self.expand(rule, depth + 1)?;
+            self.variables
+                .entry(name.to_owned())
+                .or_default()
+                .push(value);
+        }
+        Ok(())
+    }
+
+    /// Expand a tag body like `[actions]symbol.modifier.modifier`.
+    fn tag(&mut self, tag: &str, depth: usize) -> Result<String, GrammarError> {
+        let mut rest = tag;
+        while rest.starts_with('[') {
+            let end = closing(rest, ']').ok_or_else(|| unclosed(tag))?;
+            self.action(&rest[1..end], depth)?;
+            rest = &rest[end + 1..];
+        }
+
+        let mut parts = rest.split('.');
+        let symbol = parts.next().unwrap_or_default();
+        let mut value = if symbol.is_empty() {
+            String::new()
+        } else if let Some(saved) = self.variables.get(symbol).and_then(|s| s.last()) {
+            saved.clone()
+        } else if let Some(expansions) = self.grammar.rules.get(symbol) {
+            let chosen = choose_weighted(self.rng, expansions, Expansion::weight)
+                .map_err(|err| GrammarError::Rule(symbol.to_owned(), err))?;
+            self.expand(chosen.text(), depth + 1)?
+        } else {
+            return Err(GrammarError::UnknownSymbol(symbol.to_owned()));
+        };
+
+        for modifier in parts {
+            value = match self.grammar.modifiers.get(modifier) {
+                Some(custom) => custom(&value),
+                None => builtin_modifier(modifier, &value)
+                    .ok_or_else(|| GrammarError::UnknownModifier(modifier.to_owned()))?,
+            };
+        }
+        Ok(value)
+    }
+}
+
+fn unclosed(text: &str) -> GrammarError {
+    GrammarError::Unclosed(text.to_owned())
+}
+
+/// Find the byte index of the delimiter closing the `[` or `#` that starts
+/// `text`, skipping escapes and anything nested inside brackets.
+fn closing(text: &str, delimiter: char) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut escaped = false;
+    for (idx, c) in text.char_indices().skip(1) {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+        match c {
+            '\\' => escaped = true,
+            '[' => depth += 1,
+            ']' if depth > 0 => depth -= 1,
+            _ if c == delimiter && depth == 0 => return Some(idx),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn builtin_modifier(name: &str, text: &str) -> Option<String> {
+    Some(match name {
+        "capitalize" => capitalize(text),
+        "capitalizeAll" => text
+            .split(' ')
+            .map(capitalize)
+            .collect::<Vec<_>>()
+            .join(" "),
+        "a" => {
+            let article = match text.chars().next() {
+                Some(c) if "aeiouAEIOU".contains(c) => "an",
+                _ => "a",
+            };
+            format!("{article} {text}")
+        }
+        "s" => plural(text),
+        "ed" => past_tense(text),
+        "inQuotes" => format!("\"{text}\""),
+        _ => return None,
+    })
+}
+
+fn ends_with_consonant_y(text: &str) -> bool {
+    let mut chars = text.chars().rev();
+    chars.next() == Some('y') && chars.next().is_some_and(|c| !"aeiou".contains(c))
+}
+
+fn plural(text: &str) -> String {
+    if ends_with_consonant_y(text) {
+        format!("{}ies", &text[..text.len() - 1])
+    } else if ["s", "x", "z", "ch", "sh"]
+        .iter()
+        .any(|suffix| text.ends_with(suffix))
+    {
+        format!("{text}es")
+    } else {
+        format!("{text}s")
+    }
+}
+
+fn past_tense(text: &str) -> String {
+    if ends_with_consonant_y(text) {
+        format!("{}ied", &text[..text.len() - 1])
+    } else if text.ends_with('e') {
+        format!("{text}d")
+    } else {
+        format!("{text}ed")
+    }
+}
+
+/// Plugin that loads [`Grammar`] assets from `.grammar.ron` and
+/// `.grammar.json` files.
+///
+/// Requires Bevy's `AssetPlugin`.
+#[cfg(feature = "asset")]
+pub struct GrammarPlugin;
+
+#[cfg(feature = "asset")]
+impl Plugin for GrammarPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<Grammar>()
+            .register_asset_loader(GrammarLoader);
+    }
+}
+
+/// Asset loader for [`Grammar`] files, registered by [`GrammarPlugin`].
+#[cfg(feature = "asset")]
+#[derive(Default, TypePath)]
+pub struct GrammarLoader;
+
+/// Error returned when a grammar asset fails to load.
+#[cfg(feature = "asset")]
+#[derive(Debug)]
+pub enum GrammarLoaderError {
+    /// The file could not be read.
+    Io(std::io::Error),
+    /// The file is not a valid RON grammar.
+    Ron(ron::error::SpannedError),
+    /// The file is not a valid JSON grammar.
+    Json(serde_json::Error),
+}
+
+#[cfg(feature = "asset")]
+impl fmt::Display for GrammarLoaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "could not read grammar: {err}"),
+            Self::Ron(err) => write!(f, "invalid RON grammar: {err}"),
+            Self::Json(err) => write!(f, "invalid JSON grammar: {err}"),
+        }
+    }
+}
+
+#[cfg(feature = "asset")]
+impl std::error::Error for GrammarLoaderError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            Self::Ron(err) => Some(err),
+            Self::Json(err) => Some(err),
+        }
+    }
+}
+
+#[cfg(feature = "asset")]
+impl GrammarLoader {
+    fn parse(bytes: &[u8], json: bool) -> Result<Grammar, GrammarLoaderError> {
+        if json {
+            serde_json::from_slice(bytes).map_err(GrammarLoaderError::Json)
+        } else {
+            ron::de::from_bytes(bytes).map_err(GrammarLoaderError::Ron)
+        }
+    }
+}
+
+#[cfg(feature = "asset")]
+impl bevy::asset::AssetLoader for GrammarLoader {
+    type Asset = Grammar;
+    type Settings = ();
+    type Error = GrammarLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn bevy::asset::io::Reader,
+        _settings: &(),
+        load_context: &mut bevy::asset::LoadContext<'_>,
+    ) -> Result<Grammar, GrammarLoaderError> {
+        let mut bytes = Vec::new();
+        reader
+            .read_to_end(&mut bytes)
+            .await
+            .map_err(GrammarLoaderError::Io)?;
+        let json = load_context
+            .path()
+            .get_full_extension()
+            .is_some_and(|ext| ext.ends_with("json"));
+        Self::parse(&bytes, json)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["grammar.ron", "grammar.json"]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EntityRng, GlobalRng};
+
+    fn story() -> Grammar {
+        Grammar::new()
+            .with_rule(
+                "origin",
+                ["[hero:#name#]#hero.capitalize# met #animal.a#. #hero.capitalize# left."],
+            )
+            .with_rule("name", ["aria", "bram", "corin"])
+            .with_rule("animal", ["owl", "fox", "eagle"])
+    }
+
+    #[test]
+    fn saved_variables_are_reused() {
+        let mut rng = GlobalRng::seeded(42);
+
+        for _ in 0..20 {
+            let text = story().generate("origin", &mut rng).unwrap();
+            let hero = text.split(' ').next().unwrap();
+            assert!(text.ends_with(&format!("{hero} left.")), "{text}");
+            assert!(
+                text.contains(" an owl.")
+                    || text.contains(" a fox.")
+                    || text.contains(" an eagle."),
+                "{text}"
+            );
+        }
+    }
+
+    #[test]
+    fn weights_are_respected() {
+        let grammar = Grammar::new().with_rule("loot", [("gold", 1.0), ("nothing", 0.0)]);
+        let mut rng = EntityRng::seeded(1);
+
+        for _ in 0..100 {
+            assert_eq!(grammar.generate("loot", &mut rng).unwrap(), "gold");
+        }
+    }
+
+    #[test]
+    fn modifiers_transform_text() {
+        let grammar = Grammar::new()
+            .with_rule("berry", ["berry"])
+            .with_rule("box", ["box"])
+            .with_rule("title", ["the dark tower"])
+            .with_rule("bake", ["bake"])
+            .with_modifier("shout", |text| text.to_uppercase());
+        let mut rng = GlobalRng::seeded(2);
+
+        let text = grammar
+            .expand(
+                "#berry.s# #box.s# #title.capitalizeAll# #bake.ed# #berry.shout.inQuotes# \\#literal\\#",
+                &mut rng,
+            )
+            .unwrap();
+        assert_eq!(
+            text,
+            "berries boxes The Dark Tower baked \"BERRY\" #literal#"
+        );
+    }
+
+    #[test]
+    fn pop_restores_previous_value() {
+        let grammar = Grammar::new().with_rule("x", ["outer"]);
+        let mut rng = GlobalRng::seeded(3);
+
+        let text = grammar
+            .expand("[v:#x#][v:inner]#v# [v:POP]#v#", &mut rng)
+            .unwrap();
+        assert_eq!(text, "inner outer");
+    }
+
+    #[test]
+    fn errors_are_reported() {
+        let mut rng = GlobalRng::seeded(4);
+        let grammar = Grammar::new()
+            .with_rule("loop", ["#loop#"])
+            .with_rule("empty", Vec::<Expansion>::new());
+
+        assert_eq!(
+            grammar.generate("missing", &mut rng),
+            Err(GrammarError::UnknownSymbol("missing".into()))
+        );
+        assert_eq!(
+            grammar.expand("#loop.nope#", &mut rng),
+            Err(GrammarError::RecursionLimit)
+        );
+        assert_eq!(
+            grammar.generate("empty", &mut rng),
+            Err(GrammarError::Rule("empty".into(), WeightedError::Empty))
+        );
+        assert!(matches!(
+            grammar.expand("#unclosed", &mut rng),
+            Err(GrammarError::Unclosed(_))
+        ));
+    }
+
+    #[test]
+    fn expansion_is_reproducible_from_stream() {
+        let mut global1 = GlobalRng::seeded(5);
+        let mut global2 = GlobalRng::seeded(5);
+        let mut stream1 = global1.fork_stream(7);
+        let mut stream2 = global2.fork_stream(7);
+
+        let a: Vec<String> = (0..10)
+            .map(|_| story().generate("origin", &mut stream1).unwrap())
+            .collect();
+        let b: Vec<String> = (0..10)
+            .map(|_| story().generate("origin", &mut stream2).unwrap())
+            .collect();
+        assert_eq!(a, b);
+    }
+
+    #[cfg(feature = "asset")]
+    #[test]
+    fn grammar_files_parse() {
+        let ron = br##"{
+            "origin": ["#greeting.capitalize#, traveler"],
+            "greeting": ["hello", ("well met", 2.0)],
+        }"##;
+        let json = br##"{
+            "origin": ["#greeting.capitalize#, traveler"],
+            "greeting": ["hello", ["well met", 2.0]]
+        }"##;
+        let mut rng = GlobalRng::seeded(6);
+
+        for grammar in [
+            GrammarLoader::parse(ron, false).unwrap(),
+            GrammarLoader::parse(json, true).unwrap(),
+        ] {
+            assert_eq!(
+                grammar.rule("greeting").unwrap()[1],
+                Expansion::Weighted("well met".into(), 2.0)
+            );
+            assert!(
+                grammar
+                    .generate("origin", &mut rng)
+                    .unwrap()
+                    .ends_with(", traveler")
+            );
+        }
+    }
+}
@@ -0,0 +1,186 @@
+//! A plain 2D grid used as output by the map generators.
+
+use std::ops::{Index, IndexMut};
+
+use bevy::prelude::*;
+
+/// A fixed-size 2D grid of cells stored row by row.
+///
+/// Cells are addressed by `IVec2` with `(0, 0)` at the first cell, so
+/// neighbor offsets can be added without casting. [`get`](Self::get) returns
+/// `None` outside the grid, while indexing panics.
+///
+/// # Examples
+///
+/// ```rust
+/// use bevy::prelude::*;
+/// use msg_rng::Grid;
+///
+/// let mut grid = Grid::new(UVec2::new(4, 3), false);
+/// grid[IVec2::new(1, 2)] = true;
+///
+/// assert_eq!(grid.get(IVec2::new(1, 2)), Some(&true));
+/// assert_eq!(grid.get(IVec2::new(4, 0)), None);
+/// assert_eq!(grid.count(|&open| open), 1);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Grid<T> {
+    size: UVec2,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Create a grid with every cell set to `value`.
+    #[must_use]
+    pub fn new(size: UVec2, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            size,
+            cells: vec![value; size.element_product() as usize],
+        }
+    }
+
+    /// Create a grid by calling `f` with each cell position.
+    #[must_use]
+    pub fn from_fn(size: UVec2, mut f: impl FnMut(IVec2) -> T) -> Self {
+        let cells = (0..size.y)
+            .flat_map(|y| (0..size.x).map(move |x| UVec2::new(x, y).as_ivec2()))
+            .map(&mut f)
+            .collect();
+        Self { size, cells }
+    }
+
+    /// Get the size in cells.
+    #[must_use]
+    pub fn size(&self) -> UVec2 {
+        self.size
+    }
+
+    /// Get the width in cells.
+    #[must_use]
+    pub fn width(&self) -> u32 {
+        self.size.x
+    }
+
+    /// Get the height in cells.
+    #[must_use]
+    pub fn height(&self) -> u32 {
+        self.size.y
+    }
+
+    /// Check whether `pos` lies inside the grid.
+    #[must_use]
+    pub fn contains(&self, pos: IVec2) -> bool {
+        in_bounds(self.size, pos)
+    }
+
+    /// Get the cell at `pos`, or `None` outside the grid.
+    #[must_use]
+    pub fn get(&self, pos: IVec2) -> Option<&T> {
+        self.offset(pos).map(|i| &self.cells[i])
+    }
+
+    /// Get the cell at `pos` mutably, or `None` outside the grid.
+    pub fn get_mut(&mut self, pos: IVec2) -> Option<&mut T> {
+        self.offset(pos).map(|i| &mut self.cells[i])
+    }
+
+    /// Get all cells, row by row.
+    #[must_use]
+    pub fn cells(&self) -> &[T] {
+        &self.cells
+    }
+
+    /// Iterate over every cell with its position, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (IVec2, &T)> {
+        let width = self.size.x as usize;
+        self.cells.iter().enumerate().map(move |(i, cell)| {
+            #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+            let pos = IVec2::new((i % width) as i32, (i / width) as i32);
+            (pos, cell)
+        })
+    }
+
+    /// Count the cells matching `predicate`.
+    #[must_use]
+    pub fn count(&self, mut predicate: impl FnMut(&T) -> bool) -> usize {
+        self.cells.iter().filter(|cell| predicate(cell)).count()
+    }
+
+    /// Transform every cell into a new grid of the same size.
+    #[must_use]
+    pub fn map<U>(&self, mut f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            size: self.size,
+            cells: self.cells.iter().map(&mut f).collect(),
+        }
+    }
+
+    #[allow(clippy::cast_sign_loss)]
+    fn offset(&self, pos: IVec2) -> Option<usize> {
+        self.contains(pos)
+            .then(|| pos.y as usize * self.size.x as usize + pos.x as usize)
+    }
+}
+
+/// Check whether `pos` lies inside a grid of `size` cells.
+pub(crate) fn in_bounds(size: UVec2, pos: IVec2) -> bool {
+    pos.cmpge(IVec2::ZERO).all() && pos.as_uvec2().cmplt(size).all()
+}
+
+impl<T> Index<IVec2> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: IVec2) -> &T {
+        match self.offset(pos) {
+            Some(i) => &self.cells[i],
+            None => panic!("{pos} is outside the {} grid", self.size),
+        }
+    }
+}
+
+impl<T> IndexMut<IVec2> for Grid<T> {
+    fn index_mut(&mut self, pos: IVec2) -> &mut T {
+        match self.offset(pos) {
+            Some(i) => &mut self.cells[i],
+            None => panic!("{pos} is outside the {} grid", self.size),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cells_are_stored_row_by_row() {
+        let grid = Grid::from_fn(UVec2::new(3, 2), |p| p.x + 10 * p.y);
+
+        assert_eq!(grid.cells(), &[0, 1, 2, 10, 11, 12]);
+        assert_eq!(grid[IVec2::new(2, 1)], 12);
+        assert!(grid.iter().all(|(pos, &v)| v == pos.x + 10 * pos.y));
+        assert_eq!(grid.map(|&v| v >= 10).count(|&high| high), 3);
+    }
+
+    #[test]
+    fn positions_outside_are_rejected() {
+        let mut grid = Grid::new(UVec2::new(3, 2), 0_u8);
+
+        for pos in [IVec2::new(-1, 0), IVec2::new(3, 0), IVec2::new(0, 2)] {
+            assert!(!grid.contains(pos));
+            assert_eq!(grid.get(pos), None);
+            assert_eq!(grid.get_mut(pos), None);
+        }
+        assert!(grid.contains(IVec2::new(2, 1)));
+    }
+
+    #[test]
+    #[should_panic(expected = "outside")]
+    fn indexing_outside_panics() {
+        let grid = Grid::new(UVec2::new(3, 2), 0_u8);
+        let _ = grid[IVec2::new(0, -1)];
+    }
+}
@@ -0,0 +1,387 @@
+//! Stateless hashing of seeds, grid cells and keys.
+//!
+//! Everything here is a pure function of its inputs, so per-cell decisions
+//! like tile variants or loot rolls give the same answer no matter which
+//! system asks, in which order, or on which thread.
+
+use bevy::math::{I64Vec2, I64Vec3, I64Vec4};
+use bevy::prelude::*;
+use rand::distr::uniform::{SampleRange, SampleUniform};
+use rand::rngs::StdRng;
+use rand::{RngExt, SeedableRng};
+
+use crate::{EntityRng, GlobalRng};
+
+/// Stream id mixed into RNG seeds to derive position seeds.
+pub(crate) const POSITION_STREAM: u64 = 0x9051_7104;
+
+/// SplitMix64 finalizer: every input bit affects every output bit with
+/// probability close to one half.
+#[inline]
+pub(crate) fn mix64(mut h: u64) -> u64 {
+    h = (h ^ (h >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    h = (h ^ (h >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    h ^ (h >> 31)
+}
+
+/// Combine two u64 values into a deterministic, well-mixed hash.
+///
+/// The order matters: `hash_combine(a, b)` and `hash_combine(b, a)` differ.
+#[inline]
+pub(crate) fn hash_combine(a: u64, b: u64) -> u64 {
+    mix64(a ^ mix64(b.wrapping_add(0x9E37_79B9_7F4A_7C15)))
+}
+
+/// FNV-1a, so strings hash the same on every platform and Rust version.
+pub(crate) fn hash_str(text: &str) -> u64 {
+    text.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+/// Values that can key stateless randomness: integers, integer vectors and
+/// strings.
+///
+/// Hashes are stable across platforms and releases of Rust, so they're safe
+/// to rely on for saved worlds.
+pub trait HashKey {
+    /// Reduce the key to a `u64` for hashing.
+    fn hash_key(&self) -> u64;
+}
+
+macro_rules! impl_hash_key_int {
+    ($($ty:ty),*) => {
+        $(
+            impl HashKey for $ty {
+                #[allow(clippy::cast_sign_loss, clippy::cast_lossless)]
+                fn hash_key(&self) -> u64 {
+                    *self as u64
+                }
+            }
+        )*
+    };
+}
+
+impl_hash_key_int!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+macro_rules! impl_hash_key_vec {
+    ($($ty:ty),*) => {
+        $(
+            impl HashKey for $ty {
+                fn hash_key(&self) -> u64 {
+                    self.to_array()
+                        .iter()
+                        .fold(0, |hash, component| hash_combine(hash, component.hash_key()))
+                }
+            }
+        )*
+    };
+}
+
+impl_hash_key_vec!(
+    IVec2, IVec3, IVec4, UVec2, UVec3, UVec4, I64Vec2, I64Vec3, I64Vec4
+);
+
+impl HashKey for str {
+    fn hash_key(&self) -> u64 {
+        hash_str(self)
+    }
+}
+
+impl HashKey for String {
+    fn hash_key(&self) -> u64 {
+        hash_str(self)
+    }
+}
+
+impl<T: HashKey + ?Sized> HashKey for &T {
+    fn hash_key(&self) -> u64 {
+        (**self).hash_key()
+    }
+}
+
+/// Hash `key` under `seed` into a uniformly distributed `u64`.
+///
+/// # Examples
+///
+/// ```rust
+/// use bevy::prelude::*;
+/// use msg_rng::hash_u64;
+///
+/// let cell = IVec2::new(12, -7);
+/// assert_eq!(hash_u64(42, cell), hash_u64(42, cell));
+/// assert_ne!(hash_u64(42, cell), hash_u64(43, cell));
+/// ```
+#[must_use]
+pub fn hash_u64<K: HashKey>(seed: u64, key: K) -> u64 {
+    hash_combine(seed, key.hash_key())
+}
+
+/// Hash `key` under `seed` into a uniform `f32` in `[0.0, 1.0)`.
+#[must_use]
+pub fn hash_f32<K: HashKey>(seed: u64, key: K) -> f32 {
+    unit_f32(hash_u64(seed, key))
+}
+
+/// Hash `key` under `seed` into a uniform `f64` in `[0.0, 1.0)`.
+#[must_use]
+pub fn hash_f64<K: HashKey>(seed: u64, key: K) -> f64 {
+    unit_f64(hash_u64(seed, key))
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn unit_f32(hash: u64) -> f32 {
+    (hash >> 40) as f32 / (1u64 << 24) as f32
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn unit_f64(hash: u64) -> f64 {
+    (hash >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// Deterministic randomness for one grid cell or key, without mutable state.
+///
+/// Every method is a pure function of the seed and keys, so calling the same
+/// method twice gives the same value. Use [`with`](Self::with) to derive
+/// independent values for different decisions in the same cell.
+///
+/// # Examples
+///
+/// ```rust
+/// use bevy::prelude::*;
+/// use msg_rng::PositionRng;
+///
+/// let seed = 42;
+/// let cell = IVec2::new(3, 8);
+///
+/// let tile = PositionRng::at(seed, cell);
+/// let variant = tile.with("variant").index(4);
+/// let has_flower = tile.with("flower").chance(0.1);
+///
+/// // Any system, in any order, gets the same answers
+/// assert_eq!(PositionRng::at(seed, cell).with("variant").index(4), variant);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect)]
+pub struct PositionRng {
+    hash: u64,
+}
+
+impl PositionRng {
+    /// Create randomness for `key` (usually an `IVec2` or `IVec3` cell)
+    /// under `seed`.
+    #[must_use]
+    pub fn at<K: HashKey>(seed: u64, key: K) -> Self {
+        Self {
+            hash: hash_u64(seed, key),
+        }
+    }
+
+    /// Derive independent randomness for a sub-key, e.g. a decision name or
+    /// layer index.
+    #[must_use]
+    pub fn with<K: HashKey>(self, key: K) -> Self {
+        Self::at(self.hash, key)
+    }
+
+    /// Get the hash as a uniformly distributed `u64`.
+    #[must_use]
+    pub fn u64(&self) -> u64 {
+        self.hash
+    }
+
+    /// Get a uniform `f32` in `[0.0, 1.0)`.
+    #[must_use]
+    pub fn f32(&self) -> f32 {
+        unit_f32(self.hash)
+    }
+
+    /// Get a uniform `f64` in `[0.0, 1.0)`.
+    #[must_use]
+    pub fn f64(&self) -> f64 {
+        unit_f64(self.hash)
+    }
+
+    /// Return `true` with the given probability.
+    #[must_use]
+    pub fn chance(&self, probability: f64) -> bool {
+        self.f64() < probability
+    }
+
+    /// Get an index in `0..len`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `len` is zero.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn index(&self, len: usize) -> usize {
+        assert!(len > 0, "len must be positive");
+        // Multiply-shift maps the hash onto the range without modulo bias
+        ((u128::from(self.hash) * len as u128) >> 64) as usize
+    }
+
+    /// Select an element from a slice.
+    ///
+    /// Returns `None` if the slice is empty.
+    #[must_use]
+    pub fn choose<'a, T>(&self, slice: &'a [T]) -> Option<&'a T> {
+        (!slice.is_empty()).then(|| &slice[self.index(slice.len())])
+    }
+
+    /// Get a value uniformly distributed in `range`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is empty.
+    #[must_use]
+    pub fn range<T, R>(&self, range: R) -> T
+    where
+        T: SampleUniform,
+        R: SampleRange<T>,
+    {
+        self.rng().random_range(range)
+    }
+
+    /// Create a full RNG seeded from this position, for drawing many values.
+    #[must_use]
+    pub fn rng(&self) -> StdRng {
+        StdRng::seed_from_u64(self.hash)
+    }
+}
+
+impl GlobalRng {
+    /// Get stateless randomness for `key` derived from the seed.
+    ///
+    /// This does not consume randomness from the RNG, so it's safe to call
+    /// from any system in any order.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use bevy::prelude::*;
+    /// # use msg_rng::GlobalRng;
+    /// # let rng = GlobalRng::seeded(42);
+    ///
+    /// let rotation = rng.at(IVec2::new(4, 9)).with("rotation").index(4);
+    /// assert!(rotation < 4);
+    /// ```
+    #[must_use]
+    pub fn at<K: HashKey>(&self, key: K) -> PositionRng {
+        PositionRng::at(hash_combine(self.seed(), POSITION_STREAM), key)
+    }
+}
+
+impl EntityRng {
+    /// Get stateless randomness for `key` derived from this entity's seed.
+    ///
+    /// This does not consume randomness from the RNG.
+    #[must_use]
+    pub fn at<K: HashKey>(&self, key: K) -> PositionRng {
+        PositionRng::at(hash_combine(self.seed(), POSITION_STREAM), key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Flip each input bit and check that every output bit flips about half
+    /// the time.
+    fn assert_avalanche(hash: impl Fn(u64, u64) -> u64) {
+        const SAMPLES: u32 = 2000;
+        let mut rng = StdRng::seed_from_u64(0);
+        for side in 0..2 {
+            for bit in 0..64 {
+                let mut flips = [0u32; 64];
+                for _ in 0..SAMPLES {
+                    let (a, b): (u64, u64) = (rng.random(), rng.random());
+                    let flipped = if side == 0 {
+                        hash(a ^ (1 << bit), b)
+                    } else {
+                        hash(a, b ^ (1 << bit))
+                    };
+                    let diff = hash(a, b) ^ flipped;
+                    for (out, count) in flips.iter_mut().enumerate() {
+                        *count += u32::from(diff >> out & 1 == 1);
+                    }
+                }
+                for (out, &count) in flips.iter().enumerate() {
+                    let rate = f64::from(count) / f64::from(SAMPLES);
+                    assert!(
+                        (rate - 0.5).abs() < 0.06,
+                        "input {side}:{bit} -> output {out} flips at {rate}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn hash_combine_avalanches() {
+        assert_avalanche(hash_combine);
+    }
+
+    #[test]
+    fn neighbouring_cells_are_uncorrelated() {
+        // Adjacent cells differ in one low bit; their values should still
+        // spread evenly
+        let mut buckets = [0u32; 10];
+        for x in -50..50 {
+            for y in -50..50 {
+                let value = hash_f64(7, IVec2::new(x, y));
+                assert!((0.0..1.0).contains(&value));
+                #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+                let bucket = (value * 10.0) as usize;
+                buckets[bucket] += 1;
+            }
+        }
+        for count in buckets {
+            assert!((900..1100).contains(&count), "{buckets:?}");
+        }
+
+        assert_ne!(hash_u64(7, IVec2::new(1, 2)), hash_u64(7, IVec2::new(2, 1)));
+        assert_ne!(
+            hash_u64(7, IVec2::new(1, 2)),
+            hash_u64(7, IVec3::new(1, 2, 0))
+        );
+    }
+
+    #[test]
+    fn position_rng_is_pure_and_keyed() {
+        let cell = IVec3::new(-4, 10, 2);
+        let a = PositionRng::at(1, cell);
+
+        assert_eq!(a, PositionRng::at(1, cell));
+        assert_eq!(a.f32(), a.f32());
+        assert_ne!(a.with("trees"), a.with("rocks"));
+        assert_ne!(a, PositionRng::at(2, cell));
+        assert_eq!(a.with("loot").range(1..=6), a.with("loot").range(1..=6));
+        assert_eq!(a.choose::<u8>(&[]), None);
+    }
+
+    #[test]
+    fn index_and_chance_are_uniform() {
+        let mut counts = [0u32; 3];
+        let mut hits = 0;
+        for i in 0..30_000_u32 {
+            let rng = PositionRng::at(3, i);
+            counts[rng.index(3)] += 1;
+            hits += u32::from(rng.with(0_u8).chance(0.25));
+        }
+        for count in counts {
+            assert!((9_600..10_400).contains(&count), "{counts:?}");
+        }
+        assert!((7_200..7_800).contains(&hits), "{hits}");
+    }
+
+    #[test]
+    fn rng_derived_positions_do_not_consume_state() {
+        let mut a = GlobalRng::seeded(4);
+        let mut b = GlobalRng::seeded(4);
+
+        assert_eq!(a.at(IVec2::ONE), b.at(IVec2::ONE));
+        assert_eq!(a.at(IVec2::ONE), EntityRng::seeded(4).at(IVec2::ONE));
+        assert_ne!(a.at(IVec2::ONE), PositionRng::at(4, IVec2::ONE));
+        assert_eq!(a.f64(), b.f64());
+    }
+}
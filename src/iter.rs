@@ -0,0 +1,164 @@
+//! Random selection straight from iterators, including Bevy query iterators.
+
+use rand::RngExt;
+use rand::seq::IteratorRandom;
+
+use crate::weighted::checked_weight;
+use crate::{RngSource, WeightedError};
+
+/// Extension trait for picking random items from any iterator.
+///
+/// Items are selected in a single pass without collecting into a `Vec`
+/// first, which makes this a good fit for `Query::iter()`.
+///
+/// # Examples
+///
+/// ```rust
+/// use bevy::prelude::*;
+/// use msg_rng::prelude::*;
+///
+/// #[derive(Component)]
+/// struct Enemy {
+///     threat: f32,
+/// }
+///
+/// fn pick_target(enemies: Query<(Entity, &Enemy)>, mut rng: ResMut<GlobalRng>) {
+///     if let Some((entity, _)) = enemies.iter().choose_with(&mut rng) {
+///         println!("Targeting {entity}");
+///     }
+///
+///     let dangerous = enemies
+///         .iter()
+///         .choose_weighted_by(|(_, enemy)| f64::from(enemy.threat), &mut rng);
+/// }
+/// ```
+pub trait RngIter: Iterator + Sized {
+    /// Select a random item uniformly.
+    ///
+    /// Returns `None` if the iterator is empty.
+    fn choose_with<R: RngSource + ?Sized>(self, rng: &mut R) -> Option<Self::Item> {
+        self.choose(rng.rng_mut())
+    }
+
+    /// Select a random item with probability proportional to its weight.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`WeightedError::Empty`] if the iterator is empty,
+    /// [`WeightedError::InvalidWeight`] if any weight is negative or not
+    /// finite, and [`WeightedError::AllWeightsZero`] if nothing has a
+    /// positive weight.
+    fn choose_weighted_by<F, R>(
+        self,
+        mut weight: F,
+        rng: &mut R,
+    ) -> Result<Self::Item, WeightedError>
+    where
+        F: FnMut(&Self::Item) -> f64,
+        R: RngSource + ?Sized,
+    {
+        let rng = rng.rng_mut();
+        let mut total = 0.0;
+        let mut chosen = None;
+        let mut seen_any = false;
+
+        // Weighted reservoir of size one: keep each item with probability w / total
+        for item in self {
+            seen_any = true;
+            let w = checked_weight(weight(&item))?;
+            if w > 0.0 {
+                total += w;
+                if chosen.is_none() || rng.random::<f64>() * total < w {
+                    chosen = Some(item);
+                }
+            }
+        }
+
+        match chosen {
+            Some(item) => Ok(item),
+            None if seen_any => Err(WeightedError::AllWeightsZero),
+            None => Err(WeightedError::Empty),
+        }
+    }
+
+    /// Select `k` distinct items using reservoir sampling.
+    ///
+    /// Returns every item if there are fewer than `k`. The order of the
+    /// returned items is not randomized.
+    fn sample_with<R: RngSource + ?Sized>(self, k: usize, rng: &mut R) -> Vec<Self::Item> {
+        self.sample(rng.rng_mut(), k)
+    }
+}
+
+impl<I: Iterator> RngIter for I {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EntityRng, GlobalRng};
+
+    #[test]
+    fn choose_with_handles_empty_and_filtered_iterators() {
+        let mut rng = GlobalRng::seeded(42);
+
+        assert!(std::iter::empty::<u32>().choose_with(&mut rng).is_none());
+
+        let odd = (0..100)
+            .filter(|n| n % 2 == 1)
+            .choose_with(&mut rng)
+            .unwrap();
+        assert_eq!(odd % 2, 1);
+    }
+
+    #[test]
+    fn choose_weighted_by_follows_weights() {
+        let mut rng = EntityRng::seeded(5);
+        let mut counts = [0u32; 3];
+
+        for _ in 0..10_000 {
+            let idx = [1.0, 0.0, 3.0]
+                .into_iter()
+                .enumerate()
+                .choose_weighted_by(|(_, w)| *w, &mut rng)
+                .unwrap()
+                .0;
+            counts[idx] += 1;
+        }
+
+        assert_eq!(counts[1], 0);
+        assert!((2300..2700).contains(&counts[0]), "{counts:?}");
+    }
+
+    #[test]
+    fn choose_weighted_by_reports_errors() {
+        let mut rng = GlobalRng::seeded(1);
+
+        assert_eq!(
+            std::iter::empty::<f64>().choose_weighted_by(|w| *w, &mut rng),
+            Err(WeightedError::Empty)
+        );
+        assert_eq!(
+            [0.0, 0.0].into_iter().choose_weighted_by(|w| *w, &mut rng),
+            Err(WeightedError::AllWeightsZero)
+        );
+        assert_eq!(
+            [1.0, f64::INFINITY]
+                .into_iter()
+                .choose_weighted_by(|w| *w, &mut rng),
+            Err(WeightedError::InvalidWeight)
+        );
+    }
+
+    #[test]
+    fn sample_with_matches_rng_method() {
+        let mut rng1 = GlobalRng::seeded(9);
+        let mut rng2 = GlobalRng::seeded(9);
+
+        let from_trait = (0..1000).sample_with(4, &mut rng1);
+        let from_method = rng2.sample_iter(0..1000, 4);
+        assert_eq!(from_trait, from_method);
+
+        let mut forked = rng1.fork();
+        assert_eq!((0..3).sample_with(10, &mut forked).len(), 3);
+    }
+}
@@ -85,6 +85,101 @@
 use bevy::prelude::*;
 use rand::{RngExt, SeedableRng, rngs::StdRng};
 
+use hash::hash_combine;
+
+mod cave;
+mod chunk;
+#[cfg(feature = "color")]
+mod color;
+mod curve;
+mod distributions;
+mod grammar;
+mod grid;
+mod hash;
+mod iter;
+#[cfg(feature = "mesh")]
+mod mesh;
+mod names;
+#[cfg(feature = "noise")]
+pub mod noise;
+mod pity;
+mod poisson;
+mod rotation;
+mod sampling;
+mod scheduler;
+mod shape;
+mod signal;
+mod stratified;
+mod timer;
+mod vector;
+mod walk;
+mod weighted;
+
+/// Re-export of `rand`, used by derived code and handy for naming its traits.
+pub use rand;
+
+/// Derive `Distribution<Self>` for `StandardUniform`, so the type works with
+/// [`GlobalRng::random_value()`] and [`EntityRng::random_value()`].
+///
+/// - Enums pick a variant uniformly, or proportionally to `#[weight(n)]`
+///   (default weight is 1).
+/// - Struct fields and variant fields are sampled with `StandardUniform`, or
+///   uniformly from `#[range(a..b)]` / `#[range(a..=b)]`.
+///
+/// ```rust
+/// use msg_rng::prelude::*;
+/// use msg_rng::Random;
+///
+/// #[derive(Random, Debug)]
+/// enum Biome {
+///     #[weight(5)]
+///     Plains,
+///     #[weight(3)]
+///     Forest,
+///     #[weight(0.5)]
+///     Volcano,
+/// }
+///
+/// #[derive(Random)]
+/// struct Stats {
+///     #[range(1..=20)]
+///     strength: u32,
+///     #[range(0.5..1.5)]
+///     speed: f32,
+///     lucky: bool,
+/// }
+///
+/// let mut rng = GlobalRng::seeded(42);
+/// let biome: Biome = rng.random_value();
+/// let stats = rng.random_value::<Stats>();
+/// assert!((1..=20).contains(&stats.strength));
+/// ```
+#[cfg(feature = "derive")]
+pub use msg_rng_derive::Random;
+
+pub use cave::{CellularCave, Connectivity};
+pub use chunk::ChunkSeeds;
+#[cfg(feature = "color")]
+pub use color::{GoldenHues, Harmony};
+pub use curve::{CurveDistribution, CurveDistributionError, DEFAULT_CURVE_SAMPLES};
+pub use grammar::{Expansion, Grammar, GrammarError, Modifier};
+#[cfg(feature = "asset")]
+pub use grammar::{GrammarLoader, GrammarLoaderError, GrammarPlugin};
+pub use grid::Grid;
+pub use hash::{HashKey, PositionRng, hash_f32, hash_f64, hash_u64};
+pub use iter::RngIter;
+#[cfg(feature = "mesh")]
+pub use mesh::{MeshSampler, MeshSamplerError, SurfacePoint};
+pub use names::NameGenerator;
+pub use pity::{Pity, PityRamp};
+pub use poisson::{PoissonDisk2d, PoissonDisk3d};
+pub use scheduler::{EventScheduler, EventSchedulerPlugin, ScheduledEventFired};
+pub use signal::{NoiseSignal, SignalKind};
+pub use stratified::SamplePattern;
+pub use timer::{RandomTimer, RandomTimerFinished, RandomTimerPlugin, TimerInterval};
+pub use walk::RandomWalk;
+pub use weighted::WeightedError;
+
 /// Plugin for adding centralized RNG to a Bevy app.
 ///
 /// # Examples
@@ -520,17 +615,6 @@ impl EntityRng {
     }
 }
 
-/// Combine two u64 values into a deterministic hash.
-#[inline]
-fn hash_combine(a: u64, b: u64) -> u64 {
-    // FNV-1a inspired mixing
-    let mut h = a;
-    h ^= b;
-    h = h.wrapping_mul(0x517c_c1b7_2722_0a95);
-    h ^= h >> 32;
-    h
-}
-
 /// Extension trait for creating temporary forked RNGs.
 ///
 /// Useful when you need a scoped RNG that doesn't affect the global state.
@@ -552,12 +636,56 @@ impl RngFork for EntityRng {
     }
 }
 
+/// Unified access to the generator behind any of this crate's RNG types.
+///
+/// Implemented for [`GlobalRng`], [`EntityRng`], forked [`StdRng`]s, and the
+/// `ResMut`/`Mut` wrappers systems receive, so helper types can take
+/// `&mut rng` regardless of where the randomness comes from.
+pub trait RngSource {
+    /// Get mutable access to the underlying RNG.
+    fn rng_mut(&mut self) -> &mut StdRng;
+}
+
+impl RngSource for GlobalRng {
+    fn rng_mut(&mut self) -> &mut StdRng {
+        &mut self.rng
+    }
+}
+
+impl RngSource for EntityRng {
+    fn rng_mut(&mut self) -> &mut StdRng {
+        &mut self.rng
+    }
+}
+
+impl RngSource for StdRng {
+    fn rng_mut(&mut self) -> &mut StdRng {
+        self
+    }
+}
+
+impl<T: RngSource + Resource> RngSource for ResMut<'_, T> {
+    fn rng_mut(&mut self) -> &mut StdRng {
+        self.as_mut().rng_mut()
+    }
+}
+
+impl<T: RngSource> RngSource for Mut<'_, T> {
+    fn rng_mut(&mut self) -> &mut StdRng {
+        self.as_mut().rng_mut()
+    }
+}
+
 /// Convenience type alias for a mutable reference to `GlobalRng`.
 pub type GlobalRngMut<'w> = ResMut<'w, GlobalRng>;
 
 /// Prelude module for convenient imports.
 pub mod prelude {
-    pub use super::{EntityRng, GlobalRng, GlobalRngMut, RngFork, RngPlugin};
+    pub use super::{
+        ChunkSeeds, EntityRng, EventScheduler, EventSchedulerPlugin, GlobalRng, GlobalRngMut, Pity,
+        PityRamp, PositionRng, RandomTimer, RandomTimerFinished, RandomTimerPlugin, RngFork,
+        RngIter, RngPlugin, RngSource, ScheduledEventFired, TimerInterval,
+    };
 }
 
 #[cfg(test)]
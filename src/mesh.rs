@@ -0,0 +1,414 @@
+//! Random points on mesh surfaces.
+//!
+//! [`MeshSampler`] precomputes a cumulative area table over a mesh's
+//! triangles, so each sample picks a triangle proportionally to its area with
+//! a binary search and then a uniform point inside it. Building the table once
+//! keeps per-sample cost low for particle emitters and scatter passes.
+
+use std::fmt;
+
+use bevy::mesh::{Indices, Mesh, PrimitiveTopology, VertexAttributeValues};
+use bevy::prelude::*;
+use rand::{Rng, RngExt};
+
+use crate::RngSource;
+
+/// Error returned when building a [`MeshSampler`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MeshSamplerError {
+    /// The mesh topology is not `TriangleList` or `TriangleStrip`.
+    WrongTopology,
+    /// The mesh has no `Float32x3` position attribute.
+    MissingPositions,
+    /// An index references a vertex that does not exist.
+    BadIndices,
+    /// The mesh has no triangles with a non-zero area.
+    NoSurface,
+}
+
+impl fmt::Display for MeshSamplerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::WrongTopology => write!(f, "mesh topology is not a triangle list or strip"),
+            Self::MissingPositions => write!(f, "mesh has no Float32x3 positions"),
+            Self::BadIndices => write!(f, "mesh indices reference missing vertices"),
+            Self::NoSurface => write!(f, "mesh has no triangles with non-zero area"),
+        }
+    }
+}
+
+impl std::error::Error for MeshSamplerError {}
+
+/// A random point on a mesh surface, in the mesh's local space.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SurfacePoint {
+    /// Position on the surface.
+    pub position: Vec3,
+    /// Interpolated vertex normal, or the face normal if the mesh has no
+    /// normals.
+    pub normal: Dir3,
+    /// Interpolated texture coordinates, if the mesh has `ATTRIBUTE_UV_0`.
+    pub uv: Option<Vec2>,
+}
+
+/// Uniform sampler over the surface of a [`Mesh`].
+///
+/// # Examples
+///
+/// ```rust
+/// use bevy::prelude::*;
+/// use msg_rng::prelude::*;
+/// use msg_rng::MeshSampler;
+///
+/// let mut rng = GlobalRng::seeded(42);
+/// let rock = Mesh::from(Sphere::new(2.0));
+/// let sampler = MeshSampler::new(&rock).unwrap();
+///
+/// let moss = sampler.sample(&mut rng);
+/// assert!((moss.position.length() - 2.0).abs() < 0.1);
+/// assert!(moss.normal.dot(moss.position.normalize()) > 0.9);
+/// ```
+#[derive(Debug, Clone)]
+pub struct MeshSampler {
+    positions: Vec<Vec3>,
+    normals: Option<Vec<Vec3>>,
+    uvs: Option<Vec<Vec2>>,
+    triangles: Vec<[usize; 3]>,
+    /// Running total of triangle areas, parallel to `triangles`.
+    cumulative_area: Vec<f64>,
+}
+
+impl MeshSampler {
+    /// Build the area table for `mesh`.
+    ///
+    /// Indexed and non-indexed triangle lists and strips are supported.
+    /// Triangles with zero area are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the mesh isn't made of triangles, has no
+    /// positions, references missing vertices, or has no surface area.
+    pub fn new(mesh: &Mesh) -> Result<Self, MeshSamplerError> {
+        let positions: Vec<Vec3> = match mesh.attribute(Mesh::ATTRIBUTE_POSITION) {
+            Some(VertexAttributeValues::Float32x3(values)) => {
+                values.iter().copied().map(Vec3::from).collect()
+            }
+            _ => return Err(MeshSamplerError::MissingPositions),
+        };
+        let normals = match mesh.attribute(Mesh::ATTRIBUTE_NORMAL) {
+            Some(VertexAttributeValues::Float32x3(values)) if values.len() == positions.len() => {
+                Some(values.iter().copied().map(Vec3::from).collect())
+            }
+            _ => None,
+        };
+        let uvs = match mesh.attribute(Mesh::ATTRIBUTE_UV_0) {
+            Some(VertexAttributeValues::Float32x2(values)) if values.len() == positions.len() => {
+                Some(values.iter().copied().map(Vec2::from).collect())
+            }
+            _ => None,
+        };
+
+        let indices: Vec<usize> = match mesh.indices() {
+            Some(Indices::U16(indices)) => indices.iter().map(|&i| usize::from(i)).collect(),
+            Some(Indices::U32(indices)) => indices.iter().map(|&i| i as usize).collect(),
+            None => (0..positions.len()).collect(),
+        };
+        if indices.iter().any(|&i| i >= positions.len()) {
+            return Err(MeshSamplerError::BadIndices);
+        }
+
+        let corners: Vec<[usize; 3]> = match mesh.primitive_topology() {
+            PrimitiveTopology::TriangleList => indices
+                .chunks_exact(3)
+                .map(|c| [c[0], c[1], c[2]])
+                .collect(),
+            // Every other strip triangle is flipped to keep the winding
+            PrimitiveTopology::TriangleStrip => indices
+                .windows(3)
+                .enumerate()
+                .map(|(i, c)| {
+                    if i % 2 == 0 {
+                        [c[0], c[1], c[2]]
+                    } else {
+                        [c[1], c[0], c[2]]
+                    }
+                })
+                .collect(),
+            _ => return Err(MeshSamplerError::WrongTopology),
+        };
+
+        let mut triangles = Vec::with_capacity(corners.len());
+        let mut cumulative_area = Vec::with_capacity(corners.len());
+        // Summed in f64 so small triangles keep their weight on large meshes
+        let mut total = 0.0_f64;
+        for triangle in corners {
+            let area = face_cross(&positions, triangle).length() * 0.5;
+            if area > 0.0 && area.is_finite() {
+                total += f64::from(area);
+                triangles.push(triangle);
+                cumulative_area.push(total);
+            }
+        }
+        if triangles.is_empty() {
+            return Err(MeshSamplerError::NoSurface);
+        }
+
+        Ok(Self {
+            positions,
+            normals,
+            uvs,
+            triangles,
+            cumulative_area,
+        })
+    }
+
+    /// Get the total surface area.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn area(&self) -> f32 {
+        self.cumulative_area.last().copied().unwrap_or_default() as f32
+    }
+
+    /// Get the number of triangles with non-zero area.
+    #[must_use]
+    pub fn triangle_count(&self) -> usize {
+        self.triangles.len()
+    }
+
+    /// Check if the sampler interpolates texture coordinates.
+    #[must_use]
+    pub fn has_uvs(&self) -> bool {
+        self.uvs.is_some()
+    }
+
+    /// Sample a point uniformly distributed by area over the surface.
+    pub fn sample<R: RngSource + ?Sized>(&self, rng: &mut R) -> SurfacePoint {
+        self.sample_with(rng.rng_mut())
+    }
+
+    /// Sample `count` points uniformly distributed by area over the surface.
+    pub fn sample_many<R: RngSource + ?Sized>(
+        &self,
+        rng: &mut R,
+        count: usize,
+    ) -> Vec<SurfacePoint> {
+        let rng = rng.rng_mut();
+        (0..count).map(|_| self.sample_with(rng)).collect()
+    }
+
+    fn sample_with<R: Rng + ?Sized>(&self, rng: &mut R) -> SurfacePoint {
+        let total = self.cumulative_area.last().copied().unwrap_or_default();
+        let target = rng.random::<f64>() * total;
+        let index = self
+            .cumulative_area
+            .partition_point(|&area| area <= target)
+            .min(self.triangles.len() - 1);
+        let triangle = self.triangles[index];
+
+        // Folding the square root keeps barycentric samples uniform by area
+        let r1 = rng.random::<f32>().sqrt();
+        let r2: f32 = rng.random();
+        let weights = Vec3::new(1.0 - r1, r1 * (1.0 - r2), r1 * r2);
+        let position = interpolate(&self.positions, triangle, weights);
+
+        let face = Dir3::new_unchecked(face_cross(&self.positions, triangle).normalize());
+        let normal = self
+            .normals
+            .as_ref()
+            .and_then(|normals| Dir3::new(interpolate(normals, triangle, weights)).ok())
+            .unwrap_or(face);
+        let uv = self
+            .uvs
+            .as_ref()
+            .map(|uvs| interpolate(uvs, triangle, weights));
+
+        SurfacePoint {
+            position,
+            normal,
+            uv,
+        }
+    }
+}
+
+fn face_cross(positions: &[Vec3], [a, b, c]: [usize; 3]) -> Vec3 {
+    (positions[b] - positions[a]).cross(positions[c] - positions[a])
+}
+
+fn interpolate<T>(values: &[T], [a, b, c]: [usize; 3], weights: Vec3) -> T
+where
+    T: Copy + std::ops::Mul<f32, Output = T> + std::ops::Add<Output = T>,
+{
+    values[a] * weights.x + values[b] * weights.y + values[c] * weights.z
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::asset::RenderAssetUsages;
+
+    use super::*;
+    use crate::{EntityRng, GlobalRng};
+
+    /// Two triangles side by side, the right one three times the area.
+    fn uneven_quads() -> Mesh {
+        Mesh::new(
+            PrimitiveTopology::TriangleList,
+            RenderAssetUsages::default(),
+        )
+        .with_inserted_attribute(
+            Mesh::ATTRIBUTE_POSITION,
+            vec![
+                [0.0, 0.0, 0.0],
+                [1.0, 0.0, 0.0],
+                [0.0, 1.0, 0.0],
+                [1.0, 0.0, 0.0],
+                [4.0, 0.0, 0.0],
+                [1.0, 1.0, 0.0],
+            ],
+        )
+        .with_inserted_attribute(
+            Mesh::ATTRIBUTE_UV_0,
+            vec![
+                [0.0, 0.0],
+                [1.0, 0.0],
+                [0.0, 1.0],
+                [0.0, 0.0],
+                [1.0, 0.0],
+                [0.0, 1.0],
+            ],
+        )
+    }
+
+    #[test]
+    fn triangles_are_chosen_by_area() {
+        let mut rng = GlobalRng::seeded(1);
+        let sampler = MeshSampler::new(&uneven_quads()).unwrap();
+        assert!((sampler.area() - 2.0).abs() < 1e-6);
+
+        let points = sampler.sample_many(&mut rng, 20_000);
+        let right = points.iter().filter(|p| p.position.x > 1.0).count();
+        #[allow(clippy::cast_precision_loss)]
+        let fraction = right as f32 / 20_000.0;
+        assert!((fraction - 0.75).abs() < 0.015, "{fraction}");
+
+        for p in &points {
+            // Face normal of a counter-clockwise triangle in the XY plane
+            assert_eq!(p.normal, Dir3::Z);
+            let uv = p.uv.unwrap();
+            assert!(
+                uv.min_element() >= -1e-5 && uv.x + uv.y <= 1.0 + 1e-5,
+                "{uv}"
+            );
+        }
+    }
+
+    #[test]
+    fn points_lie_on_primitive_meshes() {
+        let mut rng = EntityRng::seeded(2);
+        let sampler = MeshSampler::new(&Mesh::from(Cuboid::new(2.0, 4.0, 6.0))).unwrap();
+        assert!((sampler.area() - 88.0).abs() < 1e-3);
+        assert!(sampler.has_uvs());
+
+        for _ in 0..1000 {
+            let p = sampler.sample(&mut rng);
+            let local = p.position / Vec3::new(1.0, 2.0, 3.0);
+            // On a face: one coordinate at ±1, the others within it
+            assert!((local.abs().max_element() - 1.0).abs() < 1e-5, "{local}");
+            // Cuboid normals point straight out of their face
+            assert!(p.normal.dot(local) > 0.99, "{:?} at {local}", p.normal);
+        }
+    }
+
+    #[test]
+    fn tiny_triangles_after_a_huge_one_keep_their_weight() {
+        // 2^24 area, where an f32 sum would swallow each 0.5 increment
+        let mut positions = vec![[0.0, 0.0, 0.0], [8192.0, 0.0, 0.0], [0.0, 4096.0, 0.0]];
+        for i in 0..20_000_u16 {
+            let (x, y) = (10_000.0 + f32::from(i % 100), f32::from(i / 100));
+            positions.extend([[x, y, 0.0], [x + 1.0, y, 0.0], [x, y + 1.0, 0.0]]);
+        }
+        let mesh = Mesh::new(
+            PrimitiveTopology::TriangleList,
+            RenderAssetUsages::default(),
+        )
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+        let sampler = MeshSampler::new(&mesh).unwrap();
+
+        assert!(sampler.cumulative_area.windows(2).all(|w| w[0] < w[1]));
+        assert!((f64::from(sampler.area()) - 16_787_216.0).abs() < 2.0);
+
+        // 10_000 of 16_787_216 is about 119 in 200_000
+        let mut rng = GlobalRng::seeded(6);
+        let tiny = (0..200_000)
+            .filter(|_| sampler.sample(&mut rng).position.x >= 10_000.0)
+            .count();
+        assert!((60..200).contains(&tiny), "{tiny}");
+    }
+
+    #[test]
+    fn strips_and_unindexed_meshes_are_supported() {
+        let positions = vec![
+            [0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [1.0, 1.0, 0.0],
+        ];
+        let strip = Mesh::new(
+            PrimitiveTopology::TriangleStrip,
+            RenderAssetUsages::default(),
+        )
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+        let sampler = MeshSampler::new(&strip).unwrap();
+
+        assert_eq!(sampler.triangle_count(), 2);
+        assert!((sampler.area() - 1.0).abs() < 1e-6);
+        assert!(!sampler.has_uvs());
+        // Both strip triangles keep the same winding
+        let mut rng = GlobalRng::seeded(3);
+        for p in sampler.sample_many(&mut rng, 100) {
+            assert_eq!(p.normal, Dir3::NEG_Z);
+        }
+    }
+
+    #[test]
+    fn invalid_meshes_are_rejected() {
+        let lines = Mesh::new(PrimitiveTopology::LineList, RenderAssetUsages::default())
+            .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, vec![[0.0_f32; 3]; 2]);
+        assert_eq!(
+            MeshSampler::new(&lines).unwrap_err(),
+            MeshSamplerError::WrongTopology
+        );
+
+        let empty = Mesh::new(
+            PrimitiveTopology::TriangleList,
+            RenderAssetUsages::default(),
+        );
+        assert_eq!(
+            MeshSampler::new(&empty).unwrap_err(),
+            MeshSamplerError::MissingPositions
+        );
+
+        let degenerate = Mesh::new(
+            PrimitiveTopology::TriangleList,
+            RenderAssetUsages::default(),
+        )
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, vec![[1.0_f32; 3]; 3]);
+        assert_eq!(
+            MeshSampler::new(&degenerate).unwrap_err(),
+            MeshSamplerError::NoSurface
+        );
+
+        let dangling = degenerate.with_inserted_indices(Indices::U16(vec![0, 1, 7]));
+        assert_eq!(
+            MeshSampler::new(&dangling).unwrap_err(),
+            MeshSamplerError::BadIndices
+        );
+    }
+
+    #[test]
+    fn sampling_is_deterministic() {
+        let sampler = MeshSampler::new(&Mesh::from(Sphere::new(1.0))).unwrap();
+        let a = sampler.sample_many(&mut GlobalRng::seeded(4), 10);
+        let b = sampler.sample_many(&mut EntityRng::seeded(4), 10);
+        assert_eq!(a, b);
+    }
+}
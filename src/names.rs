@@ -0,0 +1,302 @@
+//! Procedural name generation with character-level Markov chains.
+
+use std::collections::{HashMap, HashSet};
+
+use rand::RngExt;
+
+use crate::RngSource;
+
+/// Marks the start and end of a word inside the chain.
+const BOUNDARY: char = '\0';
+
+/// How many candidates [`NameGenerator::generate`] tries before giving up.
+const MAX_ATTEMPTS: usize = 1000;
+
+/// Generates names that sound like a training word list.
+///
+/// Each letter is picked based on the previous `order` letters, weighted by
+/// how often that continuation appears in the training words. Output only
+/// depends on the training data, the settings and the RNG state, so names
+/// can be regenerated from the world seed.
+///
+/// Training words are lowercased; generated names are capitalized. Names that
+/// exactly match a training word are rejected.
+///
+/// # Examples
+///
+/// ```rust
+/// use msg_rng::prelude::*;
+/// use msg_rng::NameGenerator;
+///
+/// let planets = NameGenerator::new([
+///     "mercury", "venus", "earth", "mars", "jupiter", "saturn", "uranus", "neptune",
+///     "pluto", "ceres", "eris", "haumea", "makemake", "sedna", "orcus", "quaoar",
+/// ])
+/// .with_order(2)
+/// .with_length(4, 9)
+/// .with_banned(["ass"]);
+///
+/// let mut rng = GlobalRng::seeded(42);
+/// if let Some(name) = planets.generate(&mut rng) {
+///     println!("Welcome to {name}");
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct NameGenerator {
+    words: Vec<Vec<char>>,
+    known: HashSet<String>,
+    order: usize,
+    min_len: usize,
+    max_len: usize,
+    banned: Vec<String>,
+    transitions: HashMap<Vec<char>, Vec<(char, u32)>>,
+}
+
+impl NameGenerator {
+    /// Create a generator trained on the given words, with order 2 and
+    /// lengths between 3 and 12 characters.
+    #[must_use]
+    pub fn new<I, S>(words: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let words: Vec<Vec<char>> = words
+            .into_iter()
+            .map(|word| word.as_ref().trim().to_lowercase())
+            .filter(|word| !word.is_empty())
+            .map(|word| word.chars().collect())
+            .collect();
+        let known = words.iter().map(|word| word.iter().collect()).collect();
+
+        let mut generator = Self {
+            words,
+            known,
+            order: 2,
+            min_len: 3,
+            max_len: 12,
+            banned: Vec::new(),
+            transitions: HashMap::new(),
+        };
+        generator.train();
+        generator
+    }
+
+    /// Set how many previous letters decide the next one.
+    ///
+    /// Higher orders stay closer to the training words; lower orders are more
+    /// inventive. Values below 1 are treated as 1.
+    #[must_use]
+    pub fn with_order(mut self, order: usize) -> Self {
+        self.order = order.max(1);
+        self.train();
+        self
+    }
+
+    /// Set the allowed name length in characters (inclusive).
+    #[must_use]
+    pub fn with_length(mut self, min: usize, max: usize) -> Self {
+        self.min_len = min;
+        self.max_len = max.max(min);
+        self
+    }
+
+    /// Reject names containing any of these substrings (case-insensitive).
+    #[must_use]
+    pub fn with_banned<I, S>(mut self, banned: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.banned.extend(
+            banned
+                .into_iter()
+                .map(|s| s.as_ref().to_lowercase())
+                .filter(|s| !s.is_empty()),
+        );
+        self
+    }
+
+    /// Get the chain order.
+    #[must_use]
+    pub fn order(&self) -> usize {
+        self.order
+    }
+
+    /// Generate a name.
+    ///
+    /// Returns `None` if no valid name was found within a bounded number of
+    /// attempts, e.g. when the length limits or banned substrings rule out
+    /// everything the training data can produce.
+    pub fn generate<R: RngSource + ?Sized>(&self, rng: &mut R) -> Option<String> {
+        if self.transitions.is_empty() {
+            return None;
+        }
+
+        let rng = rng.rng_mut();
+        (0..MAX_ATTEMPTS).find_map(|_| {
+            let name = self.walk(rng)?;
+            self.accepts(&name).then(|| capitalize(&name))
+        })
+    }
+
+    /// Generate `count` distinct names.
+    ///
+    /// May return fewer if the generator runs out of new names.
+    pub fn generate_many<R: RngSource + ?Sized>(&self, rng: &mut R, count: usize) -> Vec<String> {
+        let mut names = Vec::with_capacity(count);
+        let mut seen = HashSet::new();
+        let mut misses = 0;
+        while names.len() < count && misses < MAX_ATTEMPTS {
+            match self.generate(rng) {
+                Some(name) if seen.insert(name.clone()) => names.push(name),
+                Some(_) => misses += 1,
+                None => break,
+            }
+        }
+        names
+    }
+
+    fn train(&mut self) {
+        self.transitions.clear();
+        for word in &self.words {
+            let mut padded = vec![BOUNDARY; self.order];
+            padded.extend(word);
+            padded.push(BOUNDARY);
+
+            for window in padded.windows(self.order + 1) {
+                let (context, next) = window.split_at(self.order);
+                let options = self.transitions.entry(context.to_vec()).or_default();
+                match options.iter_mut().find(|(c, _)| *c == next[0]) {
+                    Some((_, count)) => *count += 1,
+                    None => options.push((next[0], 1)),
+                }
+            }
+        }
+    }
+
+    /// Walk the chain once, giving up if the name grows past the maximum.
+    fn walk(&self, rng: &mut rand::rngs::StdRng) -> Option<String> {
+        let mut context = vec![BOUNDARY; self.order];
+        let mut name = String::new();
+        let mut len = 0;
+
+        loop {
+            let options = self.transitions.get(&context)?;
+            let total: u32 = options.iter().map(|(_, count)| count).sum();
+            let mut roll = rng.random_range(0..total);
+            let next = options
+                .iter()
+                .find(|(_, count)| {
+                    if roll < *count {
+                        true
+                    } else {
+                        roll -= count;
+                        false
+                    }
+                })
+                .map(|(c, _)| *c)?;
+
+            if next == BOUNDARY {
+                return Some(name);
+            }
+            len += 1;
+            if len > self.max_len {
+                return None;
+            }
+            name.push(next);
+            context.remove(0);
+            context.push(next);
+        }
+    }
+
+    fn accepts(&self, name: &str) -> bool {
+        let len = name.chars().count();
+        len >= self.min_len
+            && len <= self.max_len
+            && !self.known.contains(name)
+            && !self
+                .banned
+                .iter()
+                .any(|banned| name.contains(banned.as_str()))
+    }
+}
+
+/// Uppercase the first character, leaving the rest as is.
+pub(crate) fn capitalize(name: &str) -> String {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EntityRng, GlobalRng};
+
+    const TOWNS: [&str; 12] = [
+        "ashford",
+        "brambleton",
+        "caldwell",
+        "dunmore",
+        "eastwick",
+        "fairhaven",
+        "glenrock",
+        "hollowmere",
+        "ironvale",
+        "kingsbridge",
+        "larkspur",
+        "millbrook",
+    ];
+
+    #[test]
+    fn names_respect_length_and_bans() {
+        let generator = NameGenerator::new(TOWNS)
+            .with_length(5, 8)
+            .with_banned(["ll", "ee"]);
+        let mut rng = GlobalRng::seeded(42);
+
+        let names = generator.generate_many(&mut rng, 20);
+        assert_eq!(names.len(), 20);
+        for name in names {
+            let lower = name.to_lowercase();
+            assert!((5..=8).contains(&lower.chars().count()), "{name}");
+            assert!(!lower.contains("ll") && !lower.contains("ee"), "{name}");
+            assert!(!TOWNS.contains(&lower.as_str()), "{name}");
+            assert!(name.starts_with(|c: char| c.is_uppercase()), "{name}");
+        }
+    }
+
+    #[test]
+    fn names_only_use_training_letters() {
+        let generator = NameGenerator::new(["abab", "baba", "abba", "baab"]).with_order(1);
+        let mut rng = EntityRng::seeded(1);
+
+        let name = generator.generate(&mut rng).unwrap();
+        assert!(name.to_lowercase().chars().all(|c| c == 'a' || c == 'b'));
+    }
+
+    #[test]
+    fn names_are_reproducible_from_seed() {
+        let generator = NameGenerator::new(TOWNS).with_order(3);
+        let mut rng1 = GlobalRng::seeded(7);
+        let mut rng2 = GlobalRng::seeded(7);
+
+        assert_eq!(
+            generator.generate_many(&mut rng1, 10),
+            generator.generate_many(&mut rng2, 10)
+        );
+    }
+
+    #[test]
+    fn impossible_constraints_return_none() {
+        let generator = NameGenerator::new(["ab", "ba"]).with_length(10, 20);
+        let mut rng = GlobalRng::seeded(3);
+        assert!(generator.generate(&mut rng).is_none());
+
+        let empty = NameGenerator::new(Vec::<String>::new());
+        assert!(empty.generate(&mut rng).is_none());
+    }
+}
@@ -0,0 +1,711 @@
+//! Seeded coherent noise for terrain, textures and other fields.
+//!
+//! Every source builds its permutation table from a `u64` seed, so noise
+//! derived from the world seed with `noise_seed()` regenerates exactly like
+//! everything else in the game. Sources and combinators implement
+//! [`NoiseFn`] for `Vec2`, `Vec3` and `Vec4` and return values in
+//! `[-1.0, 1.0]`.
+
+use bevy::prelude::*;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+
+use crate::{EntityRng, GlobalRng, hash_combine};
+
+/// Stream id mixed into RNG seeds to derive noise seeds.
+const NOISE_STREAM: u64 = 0x0015_E5EE;
+
+/// Coherent noise sampled at points of type `P`.
+pub trait NoiseFn<P> {
+    /// Sample the noise at `point`, returning a value in `[-1.0, 1.0]`.
+    fn sample(&self, point: P) -> f32;
+}
+
+impl<P, N: NoiseFn<P> + ?Sized> NoiseFn<P> for &N {
+    fn sample(&self, point: P) -> f32 {
+        (**self).sample(point)
+    }
+}
+
+/// Shuffled lattice hash shared by the noise sources.
+#[derive(Clone, PartialEq, Eq)]
+struct PermutationTable {
+    seed: u64,
+    values: [u8; 256],
+}
+
+impl std::fmt::Debug for PermutationTable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PermutationTable")
+            .field("seed", &self.seed)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PermutationTable {
+    #[allow(clippy::cast_possible_truncation)]
+    fn new(seed: u64) -> Self {
+        let mut values: [u8; 256] = std::array::from_fn(|i| i as u8);
+        values.shuffle(&mut StdRng::seed_from_u64(seed));
+        Self { seed, values }
+    }
+
+    #[allow(clippy::cast_sign_loss)]
+    fn hash<const D: usize>(&self, cell: [i32; D]) -> u8 {
+        cell.iter().fold(0, |hash, &c| {
+            self.values[(usize::from(hash) + (c & 255) as usize) & 255]
+        })
+    }
+}
+
+fn quintic(t: f32) -> f32 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn floor_i32<const D: usize>(p: [f32; D]) -> [i32; D] {
+    std::array::from_fn(|i| p[i].floor() as i32)
+}
+
+/// Dot product of `offset` with a pseudo-random unit gradient picked by `hash`.
+fn gradient_dot<const D: usize>(hash: u8, offset: [f32; D]) -> f32 {
+    const DIAGONAL: f32 = std::f32::consts::FRAC_1_SQRT_2;
+    const GRAD2: [[f32; 2]; 8] = [
+        [1.0, 0.0],
+        [-1.0, 0.0],
+        [0.0, 1.0],
+        [0.0, -1.0],
+        [DIAGONAL, DIAGONAL],
+        [-DIAGONAL, DIAGONAL],
+        [DIAGONAL, -DIAGONAL],
+        [-DIAGONAL, -DIAGONAL],
+    ];
+    let hash = usize::from(hash);
+    match D {
+        2 => {
+            let g = GRAD2[hash & 7];
+            g[0] * offset[0] + g[1] * offset[1]
+        }
+        3 => {
+            // Edges of a cube, with four repeated to fill 16 slots
+            let (a, b) = (offset[0], offset[1]);
+            let (u, v) = match hash & 15 {
+                0..=3 => (a, b),
+                4..=7 => (a, offset[2]),
+                8..=11 => (b, offset[2]),
+                12 | 14 => (a, b),
+                _ => (b, offset[2]),
+            };
+            let u = if hash & 1 == 0 { u } else { -u };
+            let v = if hash & 2 == 0 { v } else { -v };
+            (u + v) * DIAGONAL
+        }
+        _ => {
+            // Edges of a tesseract: one axis zero, the rest ±1
+            let skip = (hash >> 3) & 3;
+            let mut sum = 0.0;
+            let mut bit = 0;
+            for (axis, value) in offset.iter().enumerate() {
+                if axis != skip {
+                    sum += if hash >> bit & 1 == 0 { *value } else { -value };
+                    bit += 1;
+                }
+            }
+            sum / 3.0_f32.sqrt()
+        }
+    }
+}
+
+/// Interpolate per-corner contributions over the cell containing `p`.
+#[allow(clippy::cast_precision_loss)]
+fn lattice<const D: usize>(
+    table: &PermutationTable,
+    p: [f32; D],
+    corner: impl Fn(u8, [f32; D]) -> f32,
+) -> f32 {
+    let base = floor_i32(p);
+    let frac: [f32; D] = std::array::from_fn(|i| p[i] - base[i] as f32);
+    let fade: [f32; D] = std::array::from_fn(|i| quintic(frac[i]));
+
+    let mut total = 0.0;
+    for bits in 0..1_u32 << D {
+        let mut weight = 1.0;
+        let mut cell = base;
+        let mut offset = frac;
+        for i in 0..D {
+            if bits >> i & 1 == 1 {
+                cell[i] = cell[i].wrapping_add(1);
+                offset[i] -= 1.0;
+                weight *= fade[i];
+            } else {
+                weight *= 1.0 - fade[i];
+            }
+        }
+        total += weight * corner(table.hash(cell), offset);
+    }
+    total
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn perlin<const D: usize>(table: &PermutationTable, p: [f32; D]) -> f32 {
+    // With unit gradients the extremes are ±sqrt(D) / 2
+    let scale = 2.0 / (D as f32).sqrt();
+    (lattice(table, p, gradient_dot) * scale).clamp(-1.0, 1.0)
+}
+
+fn value<const D: usize>(table: &PermutationTable, p: [f32; D]) -> f32 {
+    lattice(table, p, |hash, _| f32::from(hash) / 127.5 - 1.0)
+}
+
+fn simplex<const D: usize>(table: &PermutationTable, p: [f32; D]) -> f32 {
+    // Measured extremes of the raw sums, mapped to ±1
+    let scale = match D {
+        2 => 99.0,
+        3 => 45.0,
+        _ => 54.0,
+    };
+    (simplex_sum(table, p) * scale).clamp(-1.0, 1.0)
+}
+
+/// Simplex noise in any dimension, using rank ordering to find the simplex.
+#[allow(clippy::cast_precision_loss)]
+fn simplex_sum<const D: usize>(table: &PermutationTable, p: [f32; D]) -> f32 {
+    let n = D as f32;
+    let skew = ((n + 1.0).sqrt() - 1.0) / n;
+    let unskew = (1.0 - 1.0 / (n + 1.0).sqrt()) / n;
+    let radius2 = if D == 2 { 0.5 } else { 0.6 };
+
+    let s = p.iter().sum::<f32>() * skew;
+    let base = floor_i32::<D>(std::array::from_fn(|i| p[i] + s));
+    let t = base.iter().map(|&b| b as f32).sum::<f32>() * unskew;
+    let origin: [f32; D] = std::array::from_fn(|i| p[i] - (base[i] as f32 - t));
+
+    // Axes with larger offsets step first along the simplex edges
+    let mut rank = [0_usize; D];
+    for a in 0..D {
+        for b in a + 1..D {
+            if origin[a] > origin[b] {
+                rank[a] += 1;
+            } else {
+                rank[b] += 1;
+            }
+        }
+    }
+
+    let mut total = 0.0;
+    for k in 0..=D {
+        let step: [i32; D] = std::array::from_fn(|i| i32::from(rank[i] + k >= D));
+        let offset: [f32; D] =
+            std::array::from_fn(|i| origin[i] - step[i] as f32 + k as f32 * unskew);
+        let falloff = radius2 - offset.iter().map(|x| x * x).sum::<f32>();
+        if falloff > 0.0 {
+            let cell: [i32; D] = std::array::from_fn(|i| base[i].wrapping_add(step[i]));
+            let falloff2 = falloff * falloff;
+            total += falloff2 * falloff2 * gradient_dot(table.hash(cell), offset);
+        }
+    }
+    total
+}
+
+/// Distances from `p` to the nearest and second-nearest feature points.
+#[allow(clippy::cast_precision_loss)]
+fn worley<const D: usize>(table: &PermutationTable, p: [f32; D]) -> (f32, f32) {
+    let base = floor_i32(p);
+    let (mut f1, mut f2) = (f32::MAX, f32::MAX);
+    for neighbour in 0..3_u32.pow(D as u32) {
+        let mut cell = base;
+        let mut code = neighbour;
+        for c in &mut cell {
+            *c = c.wrapping_add((code % 3) as i32 - 1);
+            code /= 3;
+        }
+        let hash = usize::from(table.hash(cell));
+        let distance2: f32 = (0..D)
+            .map(|i| {
+                // One jittered feature point per cell
+                let jitter = (f32::from(table.values[(hash + 67 * i + 1) & 255]) + 0.5) / 256.0;
+                let d = cell[i] as f32 + jitter - p[i];
+                d * d
+            })
+            .sum();
+        if distance2 < f1 {
+            f2 = f1;
+            f1 = distance2;
+        } else if distance2 < f2 {
+            f2 = distance2;
+        }
+    }
+    (f1.sqrt(), f2.sqrt())
+}
+
+macro_rules! impl_source {
+    ($name:ident, |$table:ident, $p:ident| $body:expr) => {
+        impl $name {
+            /// Create the noise with a permutation table shuffled by `seed`.
+            #[must_use]
+            pub fn new(seed: u64) -> Self {
+                Self {
+                    table: PermutationTable::new(seed),
+                }
+            }
+
+            /// Get the seed.
+            #[must_use]
+            pub fn seed(&self) -> u64 {
+                self.table.seed
+            }
+        }
+
+        impl_source!(@dim $name, Vec2, |$table, $p| $body);
+        impl_source!(@dim $name, Vec3, |$table, $p| $body);
+        impl_source!(@dim $name, Vec4, |$table, $p| $body);
+    };
+    (@dim $name:ident, $vec:ty, |$table:ident, $p:ident| $body:expr) => {
+        impl NoiseFn<$vec> for $name {
+            fn sample(&self, point: $vec) -> f32 {
+                let $table = &self.table;
+                let $p = point.to_array();
+                $body
+            }
+        }
+    };
+}
+
+/// Classic gradient noise, smooth with visible grid-aligned features.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Perlin {
+    table: PermutationTable,
+}
+
+impl_source!(Perlin, |table, p| perlin(table, p));
+
+/// Simplex gradient noise, with fewer directional artifacts than [`Perlin`]
+/// and cheaper in higher dimensions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Simplex {
+    table: PermutationTable,
+}
+
+impl_source!(Simplex, |table, p| simplex(table, p));
+
+/// Smoothly interpolated random lattice values, blobbier than gradient noise.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Value {
+    table: PermutationTable,
+}
+
+impl_source!(Value, |table, p| value(table, p));
+
+/// What a [`Worley`] noise measures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+pub enum WorleyReturn {
+    /// Distance to the nearest feature point, giving round cells.
+    #[default]
+    Distance,
+    /// Difference between the two nearest distances, which is zero along
+    /// cell borders, giving cracks and veins.
+    Edge,
+}
+
+/// Cellular (Voronoi) noise from one jittered feature point per lattice cell.
+///
+/// Distances are remapped so `0.0` becomes `-1.0` and one cell width becomes
+/// `1.0`; anything further is clamped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Worley {
+    table: PermutationTable,
+    output: WorleyReturn,
+}
+
+impl Worley {
+    /// Create the noise with a permutation table shuffled by `seed`.
+    #[must_use]
+    pub fn new(seed: u64) -> Self {
+        Self {
+            table: PermutationTable::new(seed),
+            output: WorleyReturn::Distance,
+        }
+    }
+
+    /// Set what the noise measures.
+    #[must_use]
+    pub fn with_return(mut self, output: WorleyReturn) -> Self {
+        self.output = output;
+        self
+    }
+
+    /// Get the seed.
+    #[must_use]
+    pub fn seed(&self) -> u64 {
+        self.table.seed
+    }
+
+    fn remap(&self, (f1, f2): (f32, f32)) -> f32 {
+        let distance = match self.output {
+            WorleyReturn::Distance => f1,
+            WorleyReturn::Edge => f2 - f1,
+        };
+        (distance * 2.0 - 1.0).clamp(-1.0, 1.0)
+    }
+}
+
+impl NoiseFn<Vec2> for Worley {
+    fn sample(&self, point: Vec2) -> f32 {
+        self.remap(worley(&self.table, point.to_array()))
+    }
+}
+
+impl NoiseFn<Vec3> for Worley {
+    fn sample(&self, point: Vec3) -> f32 {
+        self.remap(worley(&self.table, point.to_array()))
+    }
+}
+
+impl NoiseFn<Vec4> for Worley {
+    fn sample(&self, point: Vec4) -> f32 {
+        self.remap(worley(&self.table, point.to_array()))
+    }
+}
+
+/// Offsets decorrelating octaves and warp channels that share one source.
+const OFFSET: Vec4 = Vec4::new(31.416, 47.853, 12.718, 71.163);
+
+/// Fractal Brownian motion: octaves of a source at rising frequency and
+/// falling amplitude, for natural-looking terrain and clouds.
+///
+/// # Examples
+///
+/// ```rust
+/// use bevy::prelude::*;
+/// use msg_rng::noise::{Fbm, NoiseFn, Simplex};
+/// # use msg_rng::GlobalRng;
+/// # let rng = GlobalRng::seeded(42);
+///
+/// let terrain = Fbm::new(Simplex::new(rng.noise_seed(0)))
+///     .with_octaves(5)
+///     .with_frequency(0.01);
+/// let height = terrain.sample(Vec2::new(120.0, -40.0)) * 50.0;
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fbm<N> {
+    source: N,
+    octaves: u32,
+    frequency: f32,
+    lacunarity: f32,
+    persistence: f32,
+}
+
+/// Ridged multifractal noise: octaves of inverted absolute noise, giving
+/// sharp crests for mountain ranges and canyons.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ridged<N> {
+    source: N,
+    octaves: u32,
+    frequency: f32,
+    lacunarity: f32,
+    persistence: f32,
+}
+
+macro_rules! impl_fractal {
+    ($name:ident) => {
+        impl<N> $name<N> {
+            /// Wrap `source` with four octaves at frequency 1.0, lacunarity
+            /// 2.0 and persistence 0.5.
+            #[must_use]
+            pub fn new(source: N) -> Self {
+                Self {
+                    source,
+                    octaves: 4,
+                    frequency: 1.0,
+                    lacunarity: 2.0,
+                    persistence: 0.5,
+                }
+            }
+
+            /// Set the number of octaves. Values below 1 are treated as 1.
+            #[must_use]
+            pub fn with_octaves(mut self, octaves: u32) -> Self {
+                self.octaves = octaves.max(1);
+                self
+            }
+
+            /// Set the frequency of the first octave.
+            #[must_use]
+            pub fn with_frequency(mut self, frequency: f32) -> Self {
+                self.frequency = frequency;
+                self
+            }
+
+            /// Set the frequency multiplier between octaves.
+            #[must_use]
+            pub fn with_lacunarity(mut self, lacunarity: f32) -> Self {
+                self.lacunarity = lacunarity;
+                self
+            }
+
+            /// Set the amplitude multiplier between octaves.
+            #[must_use]
+            pub fn with_persistence(mut self, persistence: f32) -> Self {
+                self.persistence = persistence;
+                self
+            }
+
+            /// Sum `octave` over all octaves, normalized by total amplitude.
+            #[allow(clippy::cast_precision_loss)]
+            fn accumulate(&self, octave: impl Fn(f32, f32) -> f32) -> f32 {
+                let mut frequency = self.frequency;
+                let mut amplitude = 1.0;
+                let mut total = 0.0;
+                let mut norm = 0.0;
+                for i in 0..self.octaves {
+                    total += amplitude * octave(frequency, i as f32);
+                    norm += amplitude;
+                    frequency *= self.lacunarity;
+                    amplitude *= self.persistence;
+                }
+                if norm > 0.0 { total / norm } else { 0.0 }
+            }
+        }
+    };
+}
+
+impl_fractal!(Fbm);
+impl_fractal!(Ridged);
+
+/// Domain warping: offsets the sample point of `source` by `warp` noise,
+/// giving swirling, eroded-looking shapes.
+///
+/// # Examples
+///
+/// ```rust
+/// use bevy::prelude::*;
+/// use msg_rng::noise::{DomainWarp, Fbm, NoiseFn, Perlin};
+/// # use msg_rng::GlobalRng;
+/// # let rng = GlobalRng::seeded(42);
+///
+/// let marble = DomainWarp::new(
+///     Perlin::new(rng.noise_seed(0)),
+///     Fbm::new(Perlin::new(rng.noise_seed(1))),
+///     2.0,
+/// );
+/// let shade = marble.sample(Vec2::new(0.3, 4.2));
+/// assert!((-1.0..=1.0).contains(&shade));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct DomainWarp<N, W> {
+    source: N,
+    warp: W,
+    strength: f32,
+}
+
+impl<N, W> DomainWarp<N, W> {
+    /// Warp `source` by `warp`, displacing sample points by up to `strength`
+    /// along each axis.
+    #[must_use]
+    pub fn new(source: N, warp: W, strength: f32) -> Self {
+        Self {
+            source,
+            warp,
+            strength,
+        }
+    }
+}
+
+macro_rules! impl_combinators {
+    ($vec:ident, $truncate:expr) => {
+        impl<N: NoiseFn<$vec>> NoiseFn<$vec> for Fbm<N> {
+            fn sample(&self, point: $vec) -> f32 {
+                let offset: $vec = $truncate(OFFSET);
+                self.accumulate(|frequency, octave| {
+                    self.source.sample(point * frequency + offset * octave)
+                })
+            }
+        }
+
+        impl<N: NoiseFn<$vec>> NoiseFn<$vec> for Ridged<N> {
+            fn sample(&self, point: $vec) -> f32 {
+                let offset: $vec = $truncate(OFFSET);
+                let ridges = self.accumulate(|frequency, octave| {
+                    let n = 1.0
+                        - self
+                            .source
+                            .sample(point * frequency + offset * octave)
+                            .abs();
+                    n * n
+                });
+                ridges * 2.0 - 1.0
+            }
+        }
+
+        impl<N: NoiseFn<$vec>, W: NoiseFn<$vec>> NoiseFn<$vec> for DomainWarp<N, W> {
+            #[allow(clippy::cast_precision_loss)]
+            fn sample(&self, point: $vec) -> f32 {
+                let offset: $vec = $truncate(OFFSET);
+                let displacement = $vec::from_array(std::array::from_fn(|axis| {
+                    self.warp.sample(point + offset * (axis + 1) as f32)
+                }));
+                self.source.sample(point + displacement * self.strength)
+            }
+        }
+    };
+}
+
+impl_combinators!(Vec2, Vec4::xy);
+impl_combinators!(Vec3, Vec4::xyz);
+impl_combinators!(Vec4, std::convert::identity);
+
+impl GlobalRng {
+    /// Derive a seed for a noise source from the global seed.
+    ///
+    /// Each `channel` gives an independent seed, e.g. one for elevation and
+    /// one for moisture. This does not consume randomness from the RNG.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use bevy::prelude::*;
+    /// use msg_rng::noise::{NoiseFn, Worley};
+    /// # use msg_rng::GlobalRng;
+    /// # let rng = GlobalRng::seeded(42);
+    ///
+    /// let cells = Worley::new(rng.noise_seed(3));
+    /// let stone = cells.sample(Vec3::new(1.5, 0.2, 8.0));
+    /// ```
+    #[must_use]
+    pub fn noise_seed(&self, channel: u32) -> u64 {
+        hash_combine(hash_combine(self.seed(), NOISE_STREAM), u64::from(channel))
+    }
+}
+
+impl EntityRng {
+    /// Derive a seed for a noise source from this entity's seed.
+    ///
+    /// Each `channel` gives an independent seed. This does not consume
+    /// randomness from the RNG.
+    #[must_use]
+    pub fn noise_seed(&self, channel: u32) -> u64 {
+        hash_combine(hash_combine(self.seed(), NOISE_STREAM), u64::from(channel))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Sample a grid of points spread over several lattice cells.
+    fn grid<P>(noise: &impl NoiseFn<P>, point: impl Fn(f32, f32) -> P) -> Vec<f32> {
+        (0..100)
+            .flat_map(|x| (0..100).map(move |y| (x, y)))
+            .map(|(x, y)| noise.sample(point(x as f32 * 0.137 - 6.0, y as f32 * 0.093 - 4.0)))
+            .collect()
+    }
+
+    fn assert_spread(values: &[f32], name: &str) {
+        let (min, max) = values
+            .iter()
+            .fold((f32::MAX, f32::MIN), |(lo, hi), &v| (lo.min(v), hi.max(v)));
+        assert!(
+            (-1.0..=1.0).contains(&min) && max <= 1.0,
+            "{name}: {min}..{max}"
+        );
+        assert!(max - min > 0.8, "{name}: {min}..{max}");
+        let mean = values.iter().sum::<f32>() / values.len() as f32;
+        assert!(
+            mean.abs() < 0.35 || name.starts_with("worley"),
+            "{name}: {mean}"
+        );
+    }
+
+    fn check_source<N>(noise: &N, name: &str)
+    where
+        N: NoiseFn<Vec2> + NoiseFn<Vec3> + NoiseFn<Vec4>,
+    {
+        assert_spread(&grid(noise, Vec2::new), &format!("{name} 2d"));
+        assert_spread(
+            &grid(noise, |x, y| Vec3::new(x, y, x * 0.5)),
+            &format!("{name} 3d"),
+        );
+        assert_spread(
+            &grid(noise, |x, y| Vec4::new(x, y, y * 0.7, x * 0.3)),
+            &format!("{name} 4d"),
+        );
+    }
+
+    #[test]
+    fn sources_stay_in_range_and_vary() {
+        check_source(&Perlin::new(1), "perlin");
+        check_source(&Simplex::new(1), "simplex");
+        check_source(&Value::new(1), "value");
+        check_source(&Worley::new(1), "worley");
+        check_source(
+            &Worley::new(1).with_return(WorleyReturn::Edge),
+            "worley edge",
+        );
+    }
+
+    #[test]
+    fn noise_is_continuous() {
+        let sources: [&dyn NoiseFn<Vec3>; 4] = [
+            &Perlin::new(2),
+            &Simplex::new(2),
+            &Value::new(2),
+            &Worley::new(2),
+        ];
+        for noise in sources {
+            let mut previous = noise.sample(Vec3::new(-3.0, 0.4, 1.1));
+            for i in 1..2000 {
+                let x = -3.0 + i as f32 * 0.003;
+                let next = noise.sample(Vec3::new(x, 0.4, 1.1));
+                assert!(
+                    (next - previous).abs() < 0.05,
+                    "{previous} -> {next} at {x}"
+                );
+                previous = next;
+            }
+        }
+    }
+
+    #[test]
+    fn perlin_is_zero_on_lattice() {
+        let perlin = Perlin::new(3);
+        for x in -3..3 {
+            for y in -3..3 {
+                let p = Vec2::new(x as f32, y as f32);
+                assert!(perlin.sample(p).abs() < 1e-6);
+                assert!(perlin.sample(p.extend(2.0).extend(-1.0)).abs() < 1e-6);
+            }
+        }
+    }
+
+    #[test]
+    fn noise_follows_seed() {
+        let p = Vec2::new(0.37, 1.91);
+        assert_eq!(Simplex::new(4).sample(p), Simplex::new(4).sample(p));
+        assert_ne!(Simplex::new(4).sample(p), Simplex::new(5).sample(p));
+        assert_eq!(Value::new(4).seed(), 4);
+
+        let a = GlobalRng::seeded(4);
+        let mut b = GlobalRng::seeded(4);
+        assert_eq!(a.noise_seed(0), b.noise_seed(0));
+        assert_ne!(a.noise_seed(0), a.noise_seed(1));
+        assert_eq!(a.noise_seed(2), EntityRng::seeded(4).noise_seed(2));
+        // Deriving seeds doesn't consume randomness
+        assert_eq!(GlobalRng::seeded(4).f64(), b.f64());
+    }
+
+    #[test]
+    fn combinators_stay_in_range() {
+        let fbm = Fbm::new(Perlin::new(6)).with_octaves(6).with_frequency(0.5);
+        let ridged = Ridged::new(Simplex::new(6)).with_octaves(5);
+        let warp = DomainWarp::new(Value::new(6), Fbm::new(Simplex::new(7)), 1.5);
+
+        assert_spread(&grid(&fbm, Vec2::new), "fbm");
+        assert_spread(&grid(&ridged, |x, y| Vec3::new(x, y, 0.5)), "ridged");
+        assert_spread(&grid(&warp, Vec2::new), "warp");
+        // Octaves add detail on top of the base octave
+        let single = Fbm::new(Perlin::new(6)).with_octaves(1).with_frequency(0.5);
+        let p = Vec2::new(1.3, 2.7);
+        assert_ne!(fbm.sample(p), single.sample(p));
+    }
+}
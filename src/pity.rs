@@ -0,0 +1,210 @@
+//! Pity and bad-luck-protection counters.
+//!
+//! Gacha and loot systems commonly raise the odds after a streak of failures
+//! (soft pity) and guarantee a success after a fixed number of attempts
+//! (hard pity). [`Pity`] implements both rules on top of any [`RngSource`].
+
+use bevy::prelude::*;
+use rand::RngExt;
+
+use crate::RngSource;
+
+/// How the success chance grows once soft pity kicks in.
+#[derive(Debug, Clone, Copy, PartialEq, Reflect)]
+pub enum PityRamp {
+    /// Add a fixed amount to the chance for every failure past soft pity.
+    Linear(f64),
+    /// Multiply the chance by a fixed factor for every failure past soft pity.
+    Exponential(f64),
+}
+
+impl PityRamp {
+    fn apply(self, base: f64, steps: u32) -> f64 {
+        match self {
+            Self::Linear(step) => base + step * f64::from(steps),
+            Self::Exponential(factor) => {
+                base * factor.powi(i32::try_from(steps).unwrap_or(i32::MAX))
+            }
+        }
+    }
+}
+
+/// A probability roll with soft and hard pity.
+///
+/// The failure counter is exposed so it can be shown in UI and stored in
+/// save files, then restored with [`Pity::set_failures`].
+///
+/// # Examples
+///
+/// ```rust
+/// use msg_rng::prelude::*;
+///
+/// let mut rng = GlobalRng::seeded(42);
+///
+/// // 0.6% base chance, ramping by 6% per failure after 73 failures,
+/// // guaranteed on the 90th attempt.
+/// let mut pity = Pity::new(0.006)
+///     .with_soft_pity(73, PityRamp::Linear(0.06))
+///     .with_hard_pity(90);
+///
+/// let pulls = (1..=90).find(|_| pity.roll(&mut rng)).unwrap();
+/// assert!(pulls <= 90);
+/// assert_eq!(pity.failures(), 0);
+/// ```
+#[derive(Debug, Clone, PartialEq, Reflect)]
+pub struct Pity {
+    base_chance: f64,
+    soft_pity: Option<(u32, PityRamp)>,
+    hard_pity: Option<u32>,
+    failures: u32,
+}
+
+impl Pity {
+    /// Create a pity counter with the given base probability (0.0 to 1.0).
+    #[must_use]
+    pub fn new(base_chance: f64) -> Self {
+        Self {
+            base_chance,
+            soft_pity: None,
+            hard_pity: None,
+            failures: 0,
+        }
+    }
+
+    /// Start ramping the chance once `after_failures` consecutive failures
+    /// have happened.
+    #[must_use]
+    pub fn with_soft_pity(mut self, after_failures: u32, ramp: PityRamp) -> Self {
+        self.soft_pity = Some((after_failures, ramp));
+        self
+    }
+
+    /// Guarantee success on the given attempt since the last success.
+    #[must_use]
+    pub fn with_hard_pity(mut self, guaranteed_at: u32) -> Self {
+        self.hard_pity = Some(guaranteed_at);
+        self
+    }
+
+    /// Get the base probability.
+    #[must_use]
+    pub fn base_chance(&self) -> f64 {
+        self.base_chance
+    }
+
+    /// Get the number of consecutive failures since the last success.
+    #[must_use]
+    pub fn failures(&self) -> u32 {
+        self.failures
+    }
+
+    /// Restore the failure counter, e.g. when loading a save.
+    pub fn set_failures(&mut self, failures: u32) {
+        self.failures = failures;
+    }
+
+    /// Reset the failure counter as if a success had just happened.
+    pub fn reset(&mut self) {
+        self.failures = 0;
+    }
+
+    /// Get the probability that the next roll succeeds.
+    #[must_use]
+    pub fn current_chance(&self) -> f64 {
+        if self
+            .hard_pity
+            .is_some_and(|guaranteed_at| self.failures.saturating_add(1) >= guaranteed_at)
+        {
+            return 1.0;
+        }
+
+        let chance = match self.soft_pity {
+            Some((after_failures, ramp)) if self.failures >= after_failures => {
+                ramp.apply(self.base_chance, self.failures - after_failures + 1)
+            }
+            _ => self.base_chance,
+        };
+        chance.clamp(0.0, 1.0)
+    }
+
+    /// Get how many more attempts, including the next one, until success is
+    /// guaranteed.
+    ///
+    /// Returns `None` if there is no hard pity.
+    #[must_use]
+    pub fn attempts_until_guaranteed(&self) -> Option<u32> {
+        self.hard_pity
+            .map(|guaranteed_at| guaranteed_at.saturating_sub(self.failures).max(1))
+    }
+
+    /// Roll once, updating the failure counter.
+    ///
+    /// Always consumes exactly one value from the RNG, even when success is
+    /// guaranteed, so the stream stays aligned across replays.
+    pub fn roll<R: RngSource + ?Sized>(&mut self, rng: &mut R) -> bool {
+        let chance = self.current_chance();
+        let success = rng.rng_mut().random::<f64>() < chance;
+
+        if success {
+            self.failures = 0;
+        } else {
+            self.failures = self.failures.saturating_add(1);
+        }
+        success
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EntityRng, GlobalRng};
+
+    #[test]
+    fn hard_pity_guarantees_success() {
+        let mut rng = GlobalRng::seeded(1);
+        let mut pity = Pity::new(0.0).with_hard_pity(10);
+
+        for _ in 0..9 {
+            assert!(!pity.roll(&mut rng));
+        }
+        assert_eq!(pity.attempts_until_guaranteed(), Some(1));
+        assert!(pity.roll(&mut rng));
+        assert_eq!(pity.failures(), 0);
+    }
+
+    #[test]
+    fn soft_pity_ramps_chance() {
+        let mut pity = Pity::new(0.1).with_soft_pity(3, PityRamp::Linear(0.2));
+
+        pity.set_failures(2);
+        assert!((pity.current_chance() - 0.1).abs() < 1e-9);
+        pity.set_failures(3);
+        assert!((pity.current_chance() - 0.3).abs() < 1e-9);
+        pity.set_failures(4);
+        assert!((pity.current_chance() - 0.5).abs() < 1e-9);
+        pity.set_failures(10);
+        assert!((pity.current_chance() - 1.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn exponential_ramp_multiplies_chance() {
+        let mut pity = Pity::new(0.01).with_soft_pity(0, PityRamp::Exponential(2.0));
+
+        pity.set_failures(2);
+        assert!((pity.current_chance() - 0.08).abs() < 1e-9);
+    }
+
+    #[test]
+    fn rolls_are_deterministic_across_rng_types() {
+        let mut global = GlobalRng::seeded(7);
+        let mut entity = EntityRng::seeded(7);
+        let mut pity1 = Pity::new(0.3).with_hard_pity(5);
+        let mut pity2 = pity1.clone();
+
+        let rolls1: Vec<bool> = (0..50).map(|_| pity1.roll(&mut global)).collect();
+        let rolls2: Vec<bool> = (0..50).map(|_| pity2.roll(&mut entity)).collect();
+
+        assert_eq!(rolls1, rolls2);
+        assert_eq!(pity1, pity2);
+    }
+}
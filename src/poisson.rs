@@ -0,0 +1,568 @@
+//! Poisson disk sampling for blue-noise point placement.
+//!
+//! Points are generated with Bridson's algorithm ("Fast Poisson Disk Sampling
+//! in Arbitrary Dimensions", 2007): no two points are closer than the local
+//! radius, and new points keep being added until no more fit. The output only
+//! depends on the settings and the RNG, so worlds regenerate identically.
+
+use std::collections::HashMap;
+
+use bevy::math::bounding::Aabb3d;
+use bevy::prelude::*;
+use rand::{Rng, RngExt};
+
+use crate::RngSource;
+
+/// Candidates tried around each active point before it is retired.
+const DEFAULT_ATTEMPTS: u32 = 30;
+
+/// Random draws spent looking for the very first point, so small regions
+/// inside large bounds are still found.
+const FIRST_POINT_ATTEMPTS: u32 = 10_000;
+
+/// Poisson disk sampler over a rectangle.
+///
+/// # Examples
+///
+/// ```rust
+/// use bevy::prelude::*;
+/// use msg_rng::prelude::*;
+/// use msg_rng::PoissonDisk2d;
+///
+/// let mut rng = GlobalRng::seeded(42);
+/// let mut foliage = rng.fork_stream(7);
+///
+/// // Trees at least 2m apart, up to 6m apart away from the lake, never in it
+/// let lake = Circle::new(15.0);
+/// let trees = PoissonDisk2d::new(Rect::new(-50.0, -50.0, 50.0, 50.0), 2.0)
+///     .with_region(|p| lake.closest_point(p) != p)
+///     .with_density(6.0, |p| (p.length() / 50.0).clamp(0.0, 1.0))
+///     .generate(&mut foliage);
+///
+/// assert!(trees.iter().all(|p| p.length() > 15.0));
+/// ```
+pub struct PoissonDisk2d<'a> {
+    bounds: Rect,
+    radius: f32,
+    max_radius: f32,
+    attempts: u32,
+    density: Option<Box<dyn Fn(Vec2) -> f32 + 'a>>,
+    region: Option<Box<dyn Fn(Vec2) -> bool + 'a>>,
+}
+
+impl<'a> PoissonDisk2d<'a> {
+    /// Create a sampler placing points at least `radius` apart inside `bounds`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `radius` is not positive and finite, or if `bounds` is not
+    /// finite.
+    #[must_use]
+    pub fn new(bounds: Rect, radius: f32) -> Self {
+        check_radius(radius);
+        assert!(
+            bounds.min.is_finite() && bounds.max.is_finite(),
+            "bounds must be finite"
+        );
+        Self {
+            bounds,
+            radius,
+            max_radius: radius,
+            attempts: DEFAULT_ATTEMPTS,
+            density: None,
+            region: None,
+        }
+    }
+
+    /// Set how many candidates are tried around each point before giving up
+    /// on it. Higher values pack points more tightly but run slower.
+    #[must_use]
+    pub fn with_attempts(mut self, attempts: u32) -> Self {
+        self.attempts = attempts.max(1);
+        self
+    }
+
+    /// Only place points where `region` returns `true`.
+    ///
+    /// Use this to sample arbitrary shapes, e.g. with a primitive's
+    /// `closest_point`, or to mask out roads and water. The first point is
+    /// searched for with up to 10 000 random draws over the bounds, so a
+    /// region covering a much smaller fraction of them may produce no
+    /// points at all; keep the bounds close to the region.
+    #[must_use]
+    pub fn with_region(mut self, region: impl Fn(Vec2) -> bool + 'a) -> Self {
+        self.region = Some(Box::new(region));
+        self
+    }
+
+    /// Vary the spacing with a density callback returning values in
+    /// `[0.0, 1.0]`: density `1.0` spaces points by the base radius, `0.0` by
+    /// `max_radius`, interpolating linearly in between.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_radius` is smaller than the base radius or not finite.
+    #[must_use]
+    pub fn with_density(mut self, max_radius: f32, density: impl Fn(Vec2) -> f32 + 'a) -> Self {
+        check_max_radius(self.radius, max_radius);
+        self.max_radius = max_radius;
+        self.density = Some(Box::new(density));
+        self
+    }
+
+    /// Generate the points.
+    ///
+    /// Returns an empty `Vec` if no point could be placed, e.g. when the
+    /// region rejects every draw.
+    pub fn generate<R: RngSource + ?Sized>(&self, rng: &mut R) -> Vec<Vec2> {
+        let local_radius = |p: &[f32; 2]| {
+            self.density.as_ref().map_or(self.radius, |density| {
+                local_radius(self.radius, self.max_radius, density(Vec2::from(*p)))
+            })
+        };
+        let inside = |p: &[f32; 2]| {
+            self.region
+                .as_ref()
+                .is_none_or(|region| region(Vec2::from(*p)))
+        };
+
+        Bridson {
+            min: self.bounds.min.to_array(),
+            max: self.bounds.max.to_array(),
+            radius: self.radius,
+            max_radius: self.max_radius,
+            attempts: self.attempts,
+        }
+        .run(rng.rng_mut(), local_radius, inside)
+        .into_iter()
+        .map(Vec2::from)
+        .collect()
+    }
+}
+
+/// Poisson disk sampler over an axis-aligned box.
+///
+/// # Examples
+///
+/// ```rust
+/// use bevy::math::bounding::Aabb3d;
+/// use bevy::prelude::*;
+/// use msg_rng::prelude::*;
+/// use msg_rng::PoissonDisk3d;
+///
+/// let mut rng = GlobalRng::seeded(42);
+/// let cave = Aabb3d::new(Vec3::ZERO, Vec3::splat(10.0));
+/// let crystals = PoissonDisk3d::new(cave, 3.0).generate(&mut rng);
+/// ```
+pub struct PoissonDisk3d<'a> {
+    bounds: Aabb3d,
+    radius: f32,
+    max_radius: f32,
+    attempts: u32,
+    density: Option<Box<dyn Fn(Vec3) -> f32 + 'a>>,
+    region: Option<Box<dyn Fn(Vec3) -> bool + 'a>>,
+}
+
+impl<'a> PoissonDisk3d<'a> {
+    /// Create a sampler placing points at least `radius` apart inside `bounds`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `radius` is not positive and finite, or if `bounds` is not
+    /// finite.
+    #[must_use]
+    pub fn new(bounds: Aabb3d, radius: f32) -> Self {
+        check_radius(radius);
+        assert!(
+            bounds.min.is_finite() && bounds.max.is_finite(),
+            "bounds must be finite"
+        );
+        Self {
+            bounds,
+            radius,
+            max_radius: radius,
+            attempts: DEFAULT_ATTEMPTS,
+            density: None,
+            region: None,
+        }
+    }
+
+    /// Set how many candidates are tried around each point before giving up
+    /// on it.
+    #[must_use]
+    pub fn with_attempts(mut self, attempts: u32) -> Self {
+        self.attempts = attempts.max(1);
+        self
+    }
+
+    /// Only place points where `region` returns `true`.
+    ///
+    /// As in 2D, a region covering a tiny fraction of the bounds may produce
+    /// no points.
+    #[must_use]
+    pub fn with_region(mut self, region: impl Fn(Vec3) -> bool + 'a) -> Self {
+        self.region = Some(Box::new(region));
+        self
+    }
+
+    /// Vary the spacing with a density callback returning values in
+    /// `[0.0, 1.0]`, between the base radius at `1.0` and `max_radius` at
+    /// `0.0`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_radius` is smaller than the base radius or not finite.
+    #[must_use]
+    pub fn with_density(mut self, max_radius: f32, density: impl Fn(Vec3) -> f32 + 'a) -> Self {
+        check_max_radius(self.radius, max_radius);
+        self.max_radius = max_radius;
+        self.density = Some(Box::new(density));
+        self
+    }
+
+    /// Generate the points.
+    ///
+    /// Returns an empty `Vec` if no point could be placed.
+    pub fn generate<R: RngSource + ?Sized>(&self, rng: &mut R) -> Vec<Vec3> {
+        let local_radius = |p: &[f32; 3]| {
+            self.density.as_ref().map_or(self.radius, |density| {
+                local_radius(self.radius, self.max_radius, density(Vec3::from(*p)))
+            })
+        };
+        let inside = |p: &[f32; 3]| {
+            self.region
+                .as_ref()
+                .is_none_or(|region| region(Vec3::from(*p)))
+        };
+
+        Bridson {
+            min: Vec3::from(self.bounds.min).to_array(),
+            max: Vec3::from(self.bounds.max).to_array(),
+            radius: self.radius,
+            max_radius: self.max_radius,
+            attempts: self.attempts,
+        }
+        .run(rng.rng_mut(), local_radius, inside)
+        .into_iter()
+        .map(Vec3::from)
+        .collect()
+    }
+}
+
+fn check_radius(radius: f32) {
+    assert!(
+        radius > 0.0 && radius.is_finite(),
+        "radius must be positive and finite"
+    );
+}
+
+fn check_max_radius(radius: f32, max_radius: f32) {
+    assert!(
+        max_radius >= radius && max_radius.is_finite(),
+        "max_radius must be finite and at least the base radius"
+    );
+}
+
+fn local_radius(radius: f32, max_radius: f32, density: f32) -> f32 {
+    let density = if density.is_nan() {
+        0.0
+    } else {
+        density.clamp(0.0, 1.0)
+    };
+    max_radius + (radius - max_radius) * density
+}
+
+/// Dimension-independent core of the sampler.
+struct Bridson<const D: usize> {
+    min: [f32; D],
+    max: [f32; D],
+    radius: f32,
+    max_radius: f32,
+    attempts: u32,
+}
+
+impl<const D: usize> Bridson<D> {
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    fn run<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        local_radius: impl Fn(&[f32; D]) -> f32,
+        inside: impl Fn(&[f32; D]) -> bool,
+    ) -> Vec<[f32; D]> {
+        if (0..D).any(|axis| self.min[axis] >= self.max[axis]) {
+            return Vec::new();
+        }
+
+        // Cells are small enough that each holds at most one point
+        let cell = self.radius / (D as f32).sqrt();
+        let dims: [usize; D] = std::array::from_fn(|axis| {
+            (((self.max[axis] - self.min[axis]) / cell).ceil() as usize).max(1)
+        });
+        let mut grid = Grid {
+            cells: HashMap::new(),
+            dims,
+            min: self.min,
+            cell,
+            reach: (self.max_radius / cell).ceil() as usize,
+        };
+        let mut points: Vec<[f32; D]> = Vec::new();
+        let mut radii: Vec<f32> = Vec::new();
+        let mut active: Vec<usize> = Vec::new();
+
+        let fits = |p: &[f32; D], r: f32, grid: &Grid<D>, points: &[[f32; D]], radii: &[f32]| {
+            (0..D).all(|axis| p[axis] >= self.min[axis] && p[axis] < self.max[axis])
+                && inside(p)
+                && grid.neighbours(p).all(|i| {
+                    let limit = r.max(radii[i]);
+                    distance_squared(p, &points[i]) >= limit * limit
+                })
+        };
+
+        loop {
+            // Seed a new region; when the region callback leaves several
+            // disconnected areas, this keeps going until random seeds stop
+            // finding room
+            let budget = if points.is_empty() {
+                FIRST_POINT_ATTEMPTS.max(self.attempts)
+            } else {
+                self.attempts
+            };
+            let seed = (0..budget).find_map(|_| {
+                let p: [f32; D] =
+                    std::array::from_fn(|axis| rng.random_range(self.min[axis]..self.max[axis]));
+                let r = local_radius(&p);
+                fits(&p, r, &grid, &points, &radii).then_some((p, r))
+            });
+            let Some((p, r)) = seed else {
+                break;
+            };
+            grid.insert(&p, points.len());
+            active.push(points.len());
+            points.push(p);
+            radii.push(r);
+
+            while !active.is_empty() {
+                let slot = rng.random_range(0..active.len());
+                let (center, center_radius) = (points[active[slot]], radii[active[slot]]);
+
+                let found = (0..self.attempts).find_map(|_| {
+                    let p = annulus_point(rng, &center, center_radius);
+                    let r = local_radius(&p);
+                    fits(&p, r, &grid, &points, &radii).then_some((p, r))
+                });
+                match found {
+                    Some((p, r)) => {
+                        grid.insert(&p, points.len());
+                        active.push(points.len());
+                        points.push(p);
+                        radii.push(r);
+                    }
+                    None => {
+                        active.swap_remove(slot);
+                    }
+                }
+            }
+        }
+        points
+    }
+}
+
+/// Uniform random point between `radius` and `2 * radius` from `center`.
+fn annulus_point<R: Rng + ?Sized, const D: usize>(
+    rng: &mut R,
+    center: &[f32; D],
+    radius: f32,
+) -> [f32; D] {
+    loop {
+        let offset: [f32; D] = std::array::from_fn(|_| rng.random_range(-2.0..=2.0_f32));
+        let len2: f32 = offset.iter().map(|x| x * x).sum();
+        if (1.0..=4.0).contains(&len2) {
+            return std::array::from_fn(|axis| center[axis] + offset[axis] * radius);
+        }
+    }
+}
+
+fn distance_squared<const D: usize>(a: &[f32; D], b: &[f32; D]) -> f32 {
+    a.iter().zip(b).map(|(a, b)| (a - b) * (a - b)).sum()
+}
+
+/// Background grid for neighbour lookups, storing only occupied cells so
+/// memory follows the number of points rather than the size of the bounds.
+struct Grid<const D: usize> {
+    cells: HashMap<[usize; D], usize>,
+    dims: [usize; D],
+    min: [f32; D],
+    cell: f32,
+    /// How many cells away a conflicting point can be
+    reach: usize,
+}
+
+impl<const D: usize> Grid<D> {
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn coords(&self, p: &[f32; D]) -> [usize; D] {
+        std::array::from_fn(|axis| {
+            (((p[axis] - self.min[axis]) / self.cell) as usize).min(self.dims[axis] - 1)
+        })
+    }
+
+    fn insert(&mut self, p: &[f32; D], point: usize) {
+        self.cells.insert(self.coords(p), point);
+    }
+
+    /// Indices of points in cells within `reach` of the cell containing `p`.
+    fn neighbours(&self, p: &[f32; D]) -> impl Iterator<Item = usize> + '_ {
+        let center = self.coords(p);
+        let lo: [usize; D] = std::array::from_fn(|axis| center[axis].saturating_sub(self.reach));
+        let hi: [usize; D] =
+            std::array::from_fn(|axis| (center[axis] + self.reach).min(self.dims[axis] - 1));
+
+        let mut current = Some(lo);
+        std::iter::from_fn(move || {
+            loop {
+                let coords = current?;
+                // Advance like an odometer over the neighbourhood
+                let mut next = coords;
+                current = None;
+                for axis in 0..D {
+                    if next[axis] < hi[axis] {
+                        next[axis] += 1;
+                        current = Some(next);
+                        break;
+                    }
+                    next[axis] = lo[axis];
+                }
+
+                if let Some(&point) = self.cells.get(&coords) {
+                    return Some(point);
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GlobalRng;
+
+    fn min_distance<T: Copy>(points: &[T], distance: impl Fn(T, T) -> f32) -> f32 {
+        let mut min = f32::MAX;
+        for (i, a) in points.iter().enumerate() {
+            for b in &points[i + 1..] {
+                min = min.min(distance(*a, *b));
+            }
+        }
+        min
+    }
+
+    #[test]
+    fn points_2d_are_spaced_and_cover_the_area() {
+        let mut rng = GlobalRng::seeded(1);
+        let bounds = Rect::new(0.0, 0.0, 20.0, 20.0);
+        let points = PoissonDisk2d::new(bounds, 1.0).generate(&mut rng);
+
+        assert!(points.iter().all(|p| bounds.contains(*p)));
+        assert!(min_distance(&points, Vec2::distance) >= 1.0);
+        // Maximal: every spot is within 2 radii of some point
+        for x in 0..20 {
+            for y in 0..20 {
+                let probe = Vec2::new(x as f32 + 0.5, y as f32 + 0.5);
+                assert!(points.iter().any(|p| p.distance(probe) < 2.0), "{probe}");
+            }
+        }
+    }
+
+    #[test]
+    fn points_3d_are_spaced() {
+        let mut rng = GlobalRng::seeded(2);
+        let bounds = Aabb3d::new(Vec3::ZERO, Vec3::splat(4.0));
+        let points = PoissonDisk3d::new(bounds, 1.0).generate(&mut rng);
+
+        assert!(points.len() > 100, "{}", points.len());
+        assert!(min_distance(&points, Vec3::distance) >= 1.0);
+        assert!(points.iter().all(|p| p.abs().max_element() <= 4.0));
+    }
+
+    #[test]
+    fn region_masks_points() {
+        let mut rng = GlobalRng::seeded(3);
+        let ring = Annulus::new(3.0, 8.0);
+        let points = PoissonDisk2d::new(Rect::new(-10.0, -10.0, 10.0, 10.0), 1.0)
+            .with_region(|p| ring.closest_point(p) == p)
+            .generate(&mut rng);
+
+        assert!(points.len() > 50);
+        assert!(points.iter().all(|p| (3.0..=8.0).contains(&p.length())));
+    }
+
+    #[test]
+    fn small_regions_still_get_a_first_point() {
+        let mut rng = GlobalRng::seeded(6);
+        let bounds = Rect::new(0.0, 0.0, 100.0, 100.0);
+        // 0.04% of the bounds, which 30 draws would almost always miss
+        let corner = Rect::new(40.0, 70.0, 42.0, 72.0);
+        let points = PoissonDisk2d::new(bounds, 0.5)
+            .with_region(|p| corner.contains(p))
+            .generate(&mut rng);
+
+        assert!(points.len() > 5);
+        assert!(points.iter().all(|&p| corner.contains(p)));
+
+        // A region nothing can land in gives no points
+        let none = PoissonDisk2d::new(bounds, 0.5)
+            .with_region(|_| false)
+            .generate(&mut rng);
+        assert!(none.is_empty());
+    }
+
+    #[test]
+    fn grid_only_stores_occupied_cells() {
+        // Far more cells than could ever be allocated densely
+        let mut grid = Grid {
+            cells: HashMap::new(),
+            dims: [1 << 40; 3],
+            min: [0.0; 3],
+            cell: 1.0,
+            reach: 1,
+        };
+        grid.insert(&[5.5, 7.5, 9.5], 0);
+        grid.insert(&[1e9, 1e9, 1e9], 1);
+
+        assert_eq!(grid.cells.len(), 2);
+        assert_eq!(grid.neighbours(&[6.2, 6.9, 10.1]).collect::<Vec<_>>(), [0]);
+        assert_eq!(grid.neighbours(&[7.5, 7.5, 9.5]).count(), 0);
+        assert_eq!(grid.neighbours(&[1e9, 1e9, 1e9]).collect::<Vec<_>>(), [1]);
+    }
+
+    #[test]
+    fn density_varies_spacing() {
+        let mut rng = GlobalRng::seeded(4);
+        let points = PoissonDisk2d::new(Rect::new(0.0, 0.0, 40.0, 20.0), 1.0)
+            .with_density(3.0, |p| if p.x < 20.0 { 1.0 } else { 0.0 })
+            .generate(&mut rng);
+
+        let dense = points.iter().filter(|p| p.x < 20.0).count();
+        let sparse = points.len() - dense;
+        // Three times the spacing gives roughly a ninth of the points
+        assert!(dense > sparse * 5, "{dense} vs {sparse}");
+
+        let right: Vec<Vec2> = points.iter().copied().filter(|p| p.x >= 21.0).collect();
+        assert!(min_distance(&right, Vec2::distance) >= 3.0);
+    }
+
+    #[test]
+    fn sampling_is_deterministic_per_stream() {
+        let mut global1 = GlobalRng::seeded(5);
+        let mut global2 = GlobalRng::seeded(5);
+        let sampler = PoissonDisk2d::new(Rect::new(0.0, 0.0, 10.0, 10.0), 0.7);
+
+        assert_eq!(
+            sampler.generate(&mut global1.fork_stream(3)),
+            sampler.generate(&mut global2.fork_stream(3))
+        );
+    }
+}
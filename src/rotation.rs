@@ -0,0 +1,213 @@
+//! Uniform random rotations and directions.
+//!
+//! Randomizing Euler angles independently over-samples rotations near the
+//! poles; these methods are uniform over the space of rotations or directions.
+
+use std::f32::consts::{PI, TAU};
+
+use bevy::prelude::*;
+use rand::{Rng, RngExt};
+
+use crate::vector::vec3_on_sphere;
+use crate::{EntityRng, GlobalRng};
+
+fn quat<R: Rng + ?Sized>(rng: &mut R) -> Quat {
+    // Shoemake, "Uniform random rotations", Graphics Gems III
+    let u: f32 = rng.random();
+    let (sin_a, cos_a) = rng.random_range(0.0..TAU).sin_cos();
+    let (sin_b, cos_b) = rng.random_range(0.0..TAU).sin_cos();
+    let (low, high) = ((1.0 - u).sqrt(), u.sqrt());
+    Quat::from_xyzw(low * sin_a, low * cos_a, high * sin_b, high * cos_b).normalize()
+}
+
+fn dir2<R: Rng + ?Sized>(rng: &mut R) -> Dir2 {
+    Dir2::new_unchecked(Vec2::from_angle(rng.random_range(0.0..TAU)))
+}
+
+fn dir3<R: Rng + ?Sized>(rng: &mut R) -> Dir3 {
+    Dir3::new_unchecked(vec3_on_sphere(rng, 1.0).normalize())
+}
+
+fn dir3_in_cone<R: Rng + ?Sized>(rng: &mut R, axis: Dir3, half_angle: f32) -> Dir3 {
+    assert!(
+        half_angle >= 0.0 && half_angle.is_finite(),
+        "half_angle must be non-negative and finite"
+    );
+
+    // Like the full sphere, the height of a spherical cap is uniform by area
+    let cos_max = half_angle.min(PI).cos();
+    let z = rng.random_range(cos_max..=1.0);
+    let ring = (1.0 - z * z).max(0.0).sqrt();
+    let (sin, cos) = rng.random_range(0.0..TAU).sin_cos();
+    let local = Vec3::new(ring * cos, ring * sin, z);
+    Dir3::new_unchecked((Quat::from_rotation_arc(Vec3::Z, *axis) * local).normalize())
+}
+
+fn rotation_z<R: Rng + ?Sized>(rng: &mut R) -> Quat {
+    Quat::from_rotation_z(rng.random_range(0.0..TAU))
+}
+
+impl GlobalRng {
+    /// Generate a rotation uniformly distributed over all 3D orientations.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use bevy::prelude::*;
+    /// # use msg_rng::GlobalRng;
+    /// # let mut rng = GlobalRng::seeded(42);
+    ///
+    /// let debris = Transform::from_rotation(rng.quat());
+    /// ```
+    pub fn quat(&mut self) -> Quat {
+        quat(&mut self.rng)
+    }
+
+    /// Generate a uniformly distributed 2D direction.
+    pub fn dir2(&mut self) -> Dir2 {
+        dir2(&mut self.rng)
+    }
+
+    /// Generate a uniformly distributed 3D direction.
+    pub fn dir3(&mut self) -> Dir3 {
+        dir3(&mut self.rng)
+    }
+
+    /// Generate a direction uniformly distributed within `half_angle` radians
+    /// of `axis`, e.g. for weapon spread.
+    ///
+    /// Angles of π or more cover the whole sphere.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `half_angle` is negative or not finite.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use bevy::prelude::*;
+    /// # use msg_rng::GlobalRng;
+    /// # let mut rng = GlobalRng::seeded(42);
+    ///
+    /// let aim = Dir3::NEG_Z;
+    /// let shot = rng.dir3_in_cone(aim, 3.0_f32.to_radians());
+    /// assert!(shot.angle_between(*aim) <= 3.001_f32.to_radians());
+    /// ```
+    pub fn dir3_in_cone(&mut self, axis: Dir3, half_angle: f32) -> Dir3 {
+        dir3_in_cone(&mut self.rng, axis, half_angle)
+    }
+
+    /// Generate a uniformly distributed rotation around the Z axis, for
+    /// random facing in 2D.
+    pub fn rotation_z(&mut self) -> Quat {
+        rotation_z(&mut self.rng)
+    }
+}
+
+impl EntityRng {
+    /// Generate a rotation uniformly distributed over all 3D orientations.
+    pub fn quat(&mut self) -> Quat {
+        quat(&mut self.rng)
+    }
+
+    /// Generate a uniformly distributed 2D direction.
+    pub fn dir2(&mut self) -> Dir2 {
+        dir2(&mut self.rng)
+    }
+
+    /// Generate a uniformly distributed 3D direction.
+    pub fn dir3(&mut self) -> Dir3 {
+        dir3(&mut self.rng)
+    }
+
+    /// Generate a direction uniformly distributed within `half_angle` radians
+    /// of `axis`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `half_angle` is negative or not finite.
+    pub fn dir3_in_cone(&mut self, axis: Dir3, half_angle: f32) -> Dir3 {
+        dir3_in_cone(&mut self.rng, axis, half_angle)
+    }
+
+    /// Generate a uniformly distributed rotation around the Z axis.
+    pub fn rotation_z(&mut self) -> Quat {
+        rotation_z(&mut self.rng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const N: usize = 20_000;
+
+    #[allow(clippy::cast_precision_loss)]
+    fn fraction(count: usize) -> f32 {
+        count as f32 / N as f32
+    }
+
+    #[test]
+    fn quats_are_uniform() {
+        let mut rng = GlobalRng::seeded(1);
+        let rotated: Vec<Vec3> = (0..N)
+            .map(|_| {
+                let q = rng.quat();
+                assert!(q.is_normalized());
+                q * Vec3::Z
+            })
+            .collect();
+
+        // A uniform rotation sends any axis to a uniform direction, so each
+        // octant of the sphere gets an eighth of them
+        let octant = rotated
+            .iter()
+            .filter(|v| v.x > 0.0 && v.y > 0.0 && v.z > 0.0)
+            .count();
+        assert!((fraction(octant) - 0.125).abs() < 0.01);
+        let band = rotated.iter().filter(|v| v.z.abs() < 0.5).count();
+        assert!((fraction(band) - 0.5).abs() < 0.015);
+    }
+
+    #[test]
+    fn directions_are_uniform() {
+        let mut rng = EntityRng::seeded(2);
+
+        let flat = (0..N).filter(|_| rng.dir2().x > 0.5).count();
+        // x > 0.5 covers a third of the circle
+        assert!((fraction(flat) - 1.0 / 3.0).abs() < 0.015);
+
+        let up = (0..N).filter(|_| rng.dir3().y > 0.5).count();
+        // y > 0.5 covers a quarter of the sphere
+        assert!((fraction(up) - 0.25).abs() < 0.015);
+    }
+
+    #[test]
+    fn cone_directions_stay_in_cone() {
+        let mut rng = GlobalRng::seeded(3);
+        let axis = Dir3::new(Vec3::new(1.0, 2.0, -1.0)).unwrap();
+        let half_angle = 0.3;
+
+        let angles: Vec<f32> = (0..N)
+            .map(|_| rng.dir3_in_cone(axis, half_angle).angle_between(*axis))
+            .collect();
+        assert!(angles.iter().all(|&a| a <= half_angle + 1e-3));
+        // The inner half angle covers about a quarter of a narrow cap
+        let inner = angles.iter().filter(|&&a| a < half_angle / 2.0).count();
+        let expected = (1.0 - (half_angle / 2.0).cos()) / (1.0 - half_angle.cos());
+        assert!((fraction(inner) - expected).abs() < 0.015);
+
+        let straight = rng.dir3_in_cone(axis, 0.0);
+        assert!(straight.angle_between(*axis) < 1e-3);
+    }
+
+    #[test]
+    fn rotation_z_only_rotates_around_z() {
+        let mut rng = EntityRng::seeded(4);
+
+        for _ in 0..100 {
+            let q = rng.rotation_z();
+            assert!((q * Vec3::Z).abs_diff_eq(Vec3::Z, 1e-5));
+        }
+    }
+}
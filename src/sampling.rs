@@ -0,0 +1,151 @@
+//! Sampling without replacement for [`GlobalRng`] and [`EntityRng`].
+//!
+//! These pick `k` distinct elements directly instead of shuffling a copy of
+//! the whole collection, so allocations stay proportional to `k`.
+
+use rand::Rng;
+use rand::seq::IteratorRandom;
+
+use crate::{EntityRng, GlobalRng};
+
+fn sample_indices<R: Rng + ?Sized>(rng: &mut R, len: usize, k: usize) -> Vec<usize> {
+    rand::seq::index::sample(rng, len, k.min(len)).into_vec()
+}
+
+fn sample<'a, R: Rng + ?Sized, T>(rng: &mut R, slice: &'a [T], k: usize) -> Vec<&'a T> {
+    rand::seq::index::sample(rng, slice.len(), k.min(slice.len()))
+        .into_iter()
+        .map(|idx| &slice[idx])
+        .collect()
+}
+
+fn sample_iter<R: Rng + ?Sized, I: IntoIterator>(rng: &mut R, iter: I, k: usize) -> Vec<I::Item> {
+    iter.into_iter().sample(rng, k)
+}
+
+impl GlobalRng {
+    /// Select `k` distinct elements from a slice, in random order.
+    ///
+    /// Returns every element (shuffled) if `k` exceeds the slice length.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use msg_rng::GlobalRng;
+    /// # let mut rng = GlobalRng::seeded(42);
+    /// let spawn_points: Vec<u32> = (0..1000).collect();
+    /// let picked = rng.sample(&spawn_points, 5);
+    /// assert_eq!(picked.len(), 5);
+    /// ```
+    pub fn sample<'a, T>(&mut self, slice: &'a [T], k: usize) -> Vec<&'a T> {
+        sample(&mut self.rng, slice, k)
+    }
+
+    /// Select `k` distinct indices from `0..len`, in random order.
+    ///
+    /// Returns every index (shuffled) if `k` exceeds `len`.
+    pub fn sample_indices(&mut self, len: usize, k: usize) -> Vec<usize> {
+        sample_indices(&mut self.rng, len, k)
+    }
+
+    /// Select `k` distinct items from an iterator using reservoir sampling.
+    ///
+    /// The iterator is consumed in a single pass without being collected, so
+    /// this works with query iterators and other sources of unknown length.
+    /// Returns every item if the iterator yields fewer than `k`. The order of
+    /// the returned items is not randomized.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use msg_rng::GlobalRng;
+    /// # let mut rng = GlobalRng::seeded(42);
+    /// let evens = rng.sample_iter((0..100).filter(|n| n % 2 == 0), 3);
+    /// assert_eq!(evens.len(), 3);
+    /// ```
+    pub fn sample_iter<I: IntoIterator>(&mut self, iter: I, k: usize) -> Vec<I::Item> {
+        sample_iter(&mut self.rng, iter, k)
+    }
+}
+
+impl EntityRng {
+    /// Select `k` distinct elements from a slice, in random order.
+    pub fn sample<'a, T>(&mut self, slice: &'a [T], k: usize) -> Vec<&'a T> {
+        sample(&mut self.rng, slice, k)
+    }
+
+    /// Select `k` distinct indices from `0..len`, in random order.
+    pub fn sample_indices(&mut self, len: usize, k: usize) -> Vec<usize> {
+        sample_indices(&mut self.rng, len, k)
+    }
+
+    /// Select `k` distinct items from an iterator using reservoir sampling.
+    pub fn sample_iter<I: IntoIterator>(&mut self, iter: I, k: usize) -> Vec<I::Item> {
+        sample_iter(&mut self.rng, iter, k)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sample_returns_distinct_elements() {
+        let mut rng = GlobalRng::seeded(42);
+        let items: Vec<u32> = (0..1000).collect();
+
+        let mut picked: Vec<u32> = rng.sample(&items, 50).into_iter().copied().collect();
+        picked.sort_unstable();
+        picked.dedup();
+        assert_eq!(picked.len(), 50);
+    }
+
+    #[test]
+    fn sample_clamps_to_length() {
+        let mut rng = EntityRng::seeded(42);
+        let items = [1, 2, 3];
+
+        assert_eq!(rng.sample(&items, 10).len(), 3);
+        assert_eq!(rng.sample_indices(3, 10).len(), 3);
+        assert_eq!(rng.sample_iter(items, 10).len(), 3);
+        assert!(rng.sample::<u8>(&[], 2).is_empty());
+    }
+
+    #[test]
+    fn sample_indices_are_in_range_and_distinct() {
+        let mut rng = GlobalRng::seeded(7);
+
+        let mut indices = rng.sample_indices(100, 20);
+        assert!(indices.iter().all(|&idx| idx < 100));
+        indices.sort_unstable();
+        indices.dedup();
+        assert_eq!(indices.len(), 20);
+    }
+
+    #[test]
+    fn sample_iter_covers_all_items_uniformly() {
+        let mut rng = GlobalRng::seeded(3);
+        let mut counts = [0u32; 10];
+
+        for _ in 0..10_000 {
+            for item in rng.sample_iter(0..10usize, 3) {
+                counts[item] += 1;
+            }
+        }
+
+        // Each item should be picked ~3000 times
+        assert!(
+            counts.iter().all(|&c| (2700..3300).contains(&c)),
+            "{counts:?}"
+        );
+    }
+
+    #[test]
+    fn sampling_is_deterministic() {
+        let mut rng1 = GlobalRng::seeded(11);
+        let mut rng2 = EntityRng::seeded(11);
+
+        assert_eq!(rng1.sample_indices(500, 8), rng2.sample_indices(500, 8));
+        assert_eq!(rng1.sample_iter(0..500, 8), rng2.sample_iter(0..500, 8));
+    }
+}
@@ -0,0 +1,384 @@
+//! Random world events driven by Poisson processes.
+//!
+//! Each event registered with the [`EventScheduler`] fires at exponentially
+//! distributed intervals around its average rate. Intervals are derived from
+//! the scheduler seed, the event name and how many times the event has fired,
+//! so the schedule depends on nothing but game time: frame rate, other systems
+//! using the RNG and save/load cycles can't change when events happen.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use bevy::prelude::*;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+use crate::GlobalRng;
+use crate::distributions::exponential;
+use crate::hash::{hash_combine, hash_str};
+
+/// Stream id mixed into the global seed to seed [`EventSchedulerPlugin`]'s
+/// scheduler.
+const SCHEDULER_STREAM: u64 = 0x5EED_E7E7;
+
+/// Pending state of one event in an [`EventScheduler`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct ScheduledEvent {
+    /// Average firings per second
+    rate: f64,
+    /// Scheduler time of the next firing in seconds, `None` if the rate is zero
+    next_at: Option<f64>,
+    occurrences: u64,
+}
+
+/// Sent by [`EventSchedulerPlugin`] when a scheduled event fires.
+#[derive(Message, Debug, Clone, PartialEq, Eq)]
+pub struct ScheduledEventFired {
+    /// The name the event was registered under.
+    pub name: String,
+    /// Scheduler time at which the event fired. This can be slightly before
+    /// the current time when several firings land in the same frame.
+    pub at: Duration,
+}
+
+/// Resource that fires named events at random times with given average rates.
+///
+/// Time only advances through [`EventScheduler::advance`], which
+/// [`EventSchedulerPlugin`] calls with `Time<Virtual>`, so pausing or slowing
+/// down the game pauses or slows down the schedule too.
+///
+/// With the `serde` feature the scheduler (de)serializes, including the
+/// elapsed time and every pending firing, so it can be stored in save files.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::time::Duration;
+/// use msg_rng::EventScheduler;
+///
+/// let mut scheduler = EventScheduler::new(42)
+///     // About twice per hour of game time
+///     .with_event("meteor_shower", 2.0 / 3600.0)
+///     .with_event("merchant_visit", 1.0 / 600.0);
+///
+/// for fired in scheduler.advance(Duration::from_secs(3600)) {
+///     println!("{} at {:?}", fired.name, fired.at);
+/// }
+/// ```
+#[derive(Resource, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EventScheduler {
+    seed: u64,
+    /// Scheduler time in seconds
+    elapsed: f64,
+    events: BTreeMap<String, ScheduledEvent>,
+}
+
+impl EventScheduler {
+    /// Create an empty scheduler with the given seed.
+    #[must_use]
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            elapsed: 0.0,
+            events: BTreeMap::new(),
+        }
+    }
+
+    /// Create an empty scheduler seeded from the global seed.
+    ///
+    /// This doesn't consume randomness from the global RNG, so the schedule
+    /// only depends on the world seed.
+    #[must_use]
+    pub fn from_global(global: &GlobalRng) -> Self {
+        Self::new(hash_combine(global.seed(), SCHEDULER_STREAM))
+    }
+
+    /// Register an event firing `rate` times per second on average.
+    ///
+    /// # Panics
+    ///
+    /// See [`EventScheduler::insert`].
+    #[must_use]
+    pub fn with_event(mut self, name: impl Into<String>, rate: f64) -> Self {
+        self.insert(name, rate);
+        self
+    }
+
+    /// Register an event firing `rate` times per second on average, or change
+    /// the rate of an existing event.
+    ///
+    /// The next firing is drawn from the current time. Since the process is
+    /// memoryless, changing the rate doesn't skew the schedule. A rate of zero
+    /// keeps the event registered without ever firing it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `rate` is negative or not finite.
+    pub fn insert(&mut self, name: impl Into<String>, rate: f64) {
+        assert!(
+            rate >= 0.0 && rate.is_finite(),
+            "rate must be non-negative and finite"
+        );
+
+        let name = name.into();
+        let occurrences = self.events.get(&name).map_or(0, |event| event.occurrences);
+        let next_at = draw_next(self.seed, &name, rate, occurrences, self.elapsed);
+        self.events.insert(
+            name,
+            ScheduledEvent {
+                rate,
+                next_at,
+                occurrences,
+            },
+        );
+    }
+
+    /// Unregister an event, returning whether it was registered.
+    pub fn remove(&mut self, name: &str) -> bool {
+        self.events.remove(name).is_some()
+    }
+
+    /// Get the average rate of an event, in firings per second.
+    #[must_use]
+    pub fn rate(&self, name: &str) -> Option<f64> {
+        self.events.get(name).map(|event| event.rate)
+    }
+
+    /// Get how many times an event has fired.
+    #[must_use]
+    pub fn occurrences(&self, name: &str) -> Option<u64> {
+        self.events.get(name).map(|event| event.occurrences)
+    }
+
+    /// Get the time until an event next fires, or `None` if it is not
+    /// registered or has a rate of zero.
+    #[must_use]
+    pub fn time_until(&self, name: &str) -> Option<Duration> {
+        let next_at = self.events.get(name)?.next_at?;
+        Some(Duration::from_secs_f64((next_at - self.elapsed).max(0.0)))
+    }
+
+    /// Get the time the scheduler has advanced through.
+    #[must_use]
+    pub fn elapsed(&self) -> Duration {
+        Duration::from_secs_f64(self.elapsed)
+    }
+
+    /// Get the scheduler seed.
+    #[must_use]
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Advance the scheduler by `delta` and return the events that fired, in
+    /// the order they fired.
+    pub fn advance(&mut self, delta: Duration) -> Vec<ScheduledEventFired> {
+        self.elapsed += delta.as_secs_f64();
+
+        let mut fired = Vec::new();
+        loop {
+            // Earliest due firing; ties resolve in name order
+            let due = self
+                .events
+                .iter()
+                .filter_map(|(name, event)| Some((name, event.next_at?)))
+                .filter(|&(_, at)| at <= self.elapsed)
+                .min_by(|a, b| a.1.total_cmp(&b.1))
+                .map(|(name, at)| (name.clone(), at));
+            let Some((name, at)) = due else {
+                break;
+            };
+
+            if let Some(event) = self.events.get_mut(&name) {
+                event.occurrences += 1;
+                event.next_at = draw_next(self.seed, &name, event.rate, event.occurrences, at);
+            }
+            fired.push(ScheduledEventFired {
+                name,
+                at: Duration::from_secs_f64(at),
+            });
+        }
+        fired
+    }
+}
+
+/// Draw the scheduler time of an event's next firing after `from`.
+fn draw_next(seed: u64, name: &str, rate: f64, occurrences: u64, from: f64) -> Option<f64> {
+    if rate == 0.0 {
+        return None;
+    }
+    let seed = hash_combine(hash_combine(seed, hash_str(name)), occurrences);
+    Some(from + exponential(&mut StdRng::seed_from_u64(seed), rate))
+}
+
+/// Plugin that advances the [`EventScheduler`] with `Time<Virtual>` in
+/// `Update` and sends [`ScheduledEventFired`] messages.
+///
+/// If no scheduler has been inserted, one seeded from [`GlobalRng`] is
+/// created, so add this after [`RngPlugin`](crate::RngPlugin). Requires Bevy's
+/// `TimePlugin`.
+///
+/// # Panics
+///
+/// Panics when added without an [`EventScheduler`] or a [`GlobalRng`]
+/// resource, rather than quietly seeding the schedule at random.
+///
+/// # Examples
+///
+/// ```rust
+/// use bevy::prelude::*;
+/// use msg_rng::prelude::*;
+/// use msg_rng::{EventScheduler, EventSchedulerPlugin, ScheduledEventFired};
+///
+/// fn setup(mut scheduler: ResMut<EventScheduler>) {
+///     scheduler.insert("meteor_shower", 2.0 / 3600.0);
+/// }
+///
+/// fn on_event(mut fired: MessageReader<ScheduledEventFired>) {
+///     for event in fired.read() {
+///         if event.name == "meteor_shower" {
+///             // spawn meteors
+///         }
+///     }
+/// }
+///
+/// App::new()
+///     .add_plugins((MinimalPlugins, RngPlugin::seeded(42), EventSchedulerPlugin))
+///     .add_systems(Startup, setup)
+///     .add_systems(Update, on_event);
+/// ```
+pub struct EventSchedulerPlugin;
+
+impl Plugin for EventSchedulerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_message::<ScheduledEventFired>()
+            .add_systems(Update, advance_event_scheduler);
+
+        if !app.world().contains_resource::<EventScheduler>() {
+            let global = app.world().get_resource::<GlobalRng>().expect(
+                "EventSchedulerPlugin needs a GlobalRng: add RngPlugin before it, \
+                 or insert an EventScheduler yourself",
+            );
+            let scheduler = EventScheduler::from_global(global);
+            app.insert_resource(scheduler);
+        }
+    }
+}
+
+fn advance_event_scheduler(
+    time: Res<Time<Virtual>>,
+    mut scheduler: ResMut<EventScheduler>,
+    mut fired: MessageWriter<ScheduledEventFired>,
+) {
+    fired.write_batch(scheduler.advance(time.delta()));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn firing_count_matches_rate() {
+        let mut scheduler = EventScheduler::new(1).with_event("meteor", 2.0 / 3600.0);
+
+        // 1000 hours at 2 per hour
+        let fired = scheduler.advance(Duration::from_secs(3600 * 1000));
+        assert!((1850..2150).contains(&fired.len()), "{}", fired.len());
+        assert!(fired.windows(2).all(|pair| pair[0].at <= pair[1].at));
+        assert_eq!(scheduler.occurrences("meteor"), Some(fired.len() as u64));
+    }
+
+    #[test]
+    fn schedule_does_not_depend_on_frame_rate() {
+        let mut coarse = EventScheduler::new(2)
+            .with_event("a", 0.5)
+            .with_event("b", 0.2);
+        let mut fine = coarse.clone();
+
+        let a = coarse.advance(Duration::from_secs(100));
+        let b: Vec<_> = (0..10_000)
+            .flat_map(|_| fine.advance(Duration::from_millis(10)))
+            .collect();
+        assert_eq!(a.len(), b.len());
+        for (a, b) in a.iter().zip(&b) {
+            assert_eq!(a.name, b.name);
+            assert!(a.at.abs_diff(b.at) < Duration::from_millis(1));
+        }
+    }
+
+    #[test]
+    fn zero_rate_never_fires() {
+        let mut scheduler = EventScheduler::new(3).with_event("never", 0.0);
+
+        assert!(scheduler.advance(Duration::from_secs(1_000_000)).is_empty());
+        assert_eq!(scheduler.time_until("never"), None);
+        assert_eq!(scheduler.rate("never"), Some(0.0));
+
+        assert!(scheduler.remove("never"));
+        assert!(!scheduler.remove("never"));
+    }
+
+    #[test]
+    fn same_seed_gives_same_schedule() {
+        let global = GlobalRng::seeded(4);
+        let mut a = EventScheduler::from_global(&global).with_event("raid", 0.01);
+        let mut b = EventScheduler::from_global(&global).with_event("raid", 0.01);
+        assert_eq!(a.time_until("raid"), b.time_until("raid"));
+        assert_eq!(
+            a.advance(Duration::from_secs(10_000)),
+            b.advance(Duration::from_secs(10_000))
+        );
+
+        let other = EventScheduler::new(5).with_event("raid", 0.01);
+        assert_ne!(a.time_until("raid"), other.time_until("raid"));
+    }
+
+    #[test]
+    #[should_panic(expected = "rate must be non-negative")]
+    fn negative_rate_panics() {
+        let _ = EventScheduler::new(6).with_event("bad", -1.0);
+    }
+
+    #[test]
+    fn plugin_seeds_from_the_global_rng() {
+        let mut app = App::new();
+        app.add_plugins((crate::RngPlugin::seeded(8), EventSchedulerPlugin));
+
+        let expected = EventScheduler::from_global(&GlobalRng::seeded(8));
+        assert_eq!(app.world().resource::<EventScheduler>(), &expected);
+
+        // An inserted scheduler needs no global RNG
+        let mut app = App::new();
+        app.insert_resource(EventScheduler::new(9))
+            .add_plugins(EventSchedulerPlugin);
+        assert_eq!(
+            app.world().resource::<EventScheduler>(),
+            &EventScheduler::new(9)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "add RngPlugin before it")]
+    fn plugin_without_global_rng_panics() {
+        App::new().add_plugins(EventSchedulerPlugin);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn schedule_survives_serialization() {
+        let mut scheduler = EventScheduler::new(7)
+            .with_event("storm", 0.1)
+            .with_event("never", 0.0);
+        scheduler.advance(Duration::from_secs(30));
+
+        let saved = serde_json::to_string(&scheduler).unwrap();
+        let mut loaded: EventScheduler = serde_json::from_str(&saved).unwrap();
+        assert_eq!(loaded, scheduler);
+        assert_eq!(
+            loaded.advance(Duration::from_secs(300)),
+            scheduler.advance(Duration::from_secs(300))
+        );
+    }
+}
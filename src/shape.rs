@@ -0,0 +1,199 @@
+//! Sampling points from Bevy's math primitives.
+//!
+//! Primitives like `Circle`, `Cuboid` and `Capsule3d` implement
+//! [`ShapeSample`], which expects a generator from the previous `rand`
+//! release. The methods here bridge our RNGs to it, so any primitive can serve
+//! as a spawn region while staying on the seeded stream.
+
+use std::ops::Mul;
+
+use bevy::math::ShapeSample;
+use rand::Rng;
+use rand::rngs::StdRng;
+
+use crate::{EntityRng, GlobalRng};
+
+/// Adapter exposing one of our generators to APIs built on `rand` 0.9.
+pub(crate) struct Rng09<'a>(pub(crate) &'a mut StdRng);
+
+impl rand_core_09::RngCore for Rng09<'_> {
+    fn next_u32(&mut self) -> u32 {
+        self.0.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dst: &mut [u8]) {
+        self.0.fill_bytes(dst);
+    }
+}
+
+impl GlobalRng {
+    /// Sample a point uniformly from the interior of `shape`, centered on the
+    /// origin.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use bevy::prelude::*;
+    /// # use msg_rng::GlobalRng;
+    /// # let mut rng = GlobalRng::seeded(42);
+    ///
+    /// let pond = Circle::new(5.0);
+    /// let lily_pad: Vec2 = rng.sample_interior(&pond);
+    /// assert!(lily_pad.length() < 5.001);
+    /// ```
+    pub fn sample_interior<S: ShapeSample>(&mut self, shape: &S) -> S::Output {
+        shape.sample_interior(&mut Rng09(&mut self.rng))
+    }
+
+    /// Sample a point uniformly from the boundary of `shape`, centered on the
+    /// origin.
+    pub fn sample_boundary<S: ShapeSample>(&mut self, shape: &S) -> S::Output {
+        shape.sample_boundary(&mut Rng09(&mut self.rng))
+    }
+
+    /// Sample a point uniformly from the interior of `shape` placed at
+    /// `isometry`, returning world-space coordinates.
+    ///
+    /// Works with [`Isometry2d`](bevy::math::Isometry2d) for 2D primitives and
+    /// [`Isometry3d`](bevy::math::Isometry3d) for 3D primitives.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use bevy::prelude::*;
+    /// # use msg_rng::GlobalRng;
+    /// # let mut rng = GlobalRng::seeded(42);
+    ///
+    /// // A spawn volume lying on its side at (10, 0, 0)
+    /// let region = Capsule3d::new(1.0, 4.0);
+    /// let placement = Isometry3d::new(
+    ///     Vec3::new(10.0, 0.0, 0.0),
+    ///     Quat::from_rotation_z(std::f32::consts::FRAC_PI_2),
+    /// );
+    /// let spawn = rng.sample_interior_at(&region, placement);
+    /// ```
+    pub fn sample_interior_at<S, I>(&mut self, shape: &S, isometry: I) -> S::Output
+    where
+        S: ShapeSample,
+        I: Mul<S::Output, Output = S::Output>,
+    {
+        isometry * self.sample_interior(shape)
+    }
+
+    /// Sample a point uniformly from the boundary of `shape` placed at
+    /// `isometry`, returning world-space coordinates.
+    pub fn sample_boundary_at<S, I>(&mut self, shape: &S, isometry: I) -> S::Output
+    where
+        S: ShapeSample,
+        I: Mul<S::Output, Output = S::Output>,
+    {
+        isometry * self.sample_boundary(shape)
+    }
+}
+
+impl EntityRng {
+    /// Sample a point uniformly from the interior of `shape`, centered on the
+    /// origin.
+    pub fn sample_interior<S: ShapeSample>(&mut self, shape: &S) -> S::Output {
+        shape.sample_interior(&mut Rng09(&mut self.rng))
+    }
+
+    /// Sample a point uniformly from the boundary of `shape`, centered on the
+    /// origin.
+    pub fn sample_boundary<S: ShapeSample>(&mut self, shape: &S) -> S::Output {
+        shape.sample_boundary(&mut Rng09(&mut self.rng))
+    }
+
+    /// Sample a point uniformly from the interior of `shape` placed at
+    /// `isometry`, returning world-space coordinates.
+    pub fn sample_interior_at<S, I>(&mut self, shape: &S, isometry: I) -> S::Output
+    where
+        S: ShapeSample,
+        I: Mul<S::Output, Output = S::Output>,
+    {
+        isometry * self.sample_interior(shape)
+    }
+
+    /// Sample a point uniformly from the boundary of `shape` placed at
+    /// `isometry`, returning world-space coordinates.
+    pub fn sample_boundary_at<S, I>(&mut self, shape: &S, isometry: I) -> S::Output
+    where
+        S: ShapeSample,
+        I: Mul<S::Output, Output = S::Output>,
+    {
+        isometry * self.sample_boundary(shape)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::math::{Isometry2d, Isometry3d};
+    use bevy::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn interior_points_lie_inside() {
+        let mut rng = GlobalRng::seeded(1);
+        let cuboid = Cuboid::new(2.0, 4.0, 6.0);
+        let triangle = Triangle2d::new(Vec2::ZERO, Vec2::new(4.0, 0.0), Vec2::new(0.0, 4.0));
+
+        for _ in 0..1000 {
+            let p = rng.sample_interior(&cuboid);
+            assert!(p.abs().cmple(Vec3::new(1.0, 2.0, 3.0)).all(), "{p}");
+
+            let q = rng.sample_interior(&triangle);
+            assert!(
+                q.x >= -1e-5 && q.y >= -1e-5 && q.x + q.y <= 4.0 + 1e-4,
+                "{q}"
+            );
+        }
+    }
+
+    #[test]
+    fn boundary_points_lie_on_boundary() {
+        let mut rng = EntityRng::seeded(2);
+        let sphere = Sphere::new(3.0);
+
+        for _ in 0..1000 {
+            let p = rng.sample_boundary(&sphere);
+            assert!((p.length() - 3.0).abs() < 1e-4, "{p}");
+        }
+    }
+
+    #[test]
+    fn isometry_moves_samples_to_world_space() {
+        let mut rng = GlobalRng::seeded(3);
+        let circle = Circle::new(1.0);
+        let center = Vec2::new(10.0, -5.0);
+        let isometry = Isometry2d::new(center, Rot2::degrees(30.0));
+
+        for _ in 0..1000 {
+            let p = rng.sample_boundary_at(&circle, isometry);
+            assert!((p.distance(center) - 1.0).abs() < 1e-4, "{p}");
+        }
+
+        // A flat, rotated cuboid stays within its rotated extent
+        let slab = Cuboid::new(10.0, 0.0, 10.0);
+        let tilted = Isometry3d::from_rotation(Quat::from_rotation_x(std::f32::consts::FRAC_PI_2));
+        for _ in 0..1000 {
+            let p = rng.sample_interior_at(&slab, tilted);
+            assert!(p.z.abs() < 1e-4, "{p}");
+        }
+    }
+
+    #[test]
+    fn shape_sampling_is_deterministic() {
+        let mut rng1 = GlobalRng::seeded(4);
+        let mut rng2 = EntityRng::seeded(4);
+        let capsule = Capsule3d::new(0.5, 2.0);
+
+        let a: Vec<Vec3> = (0..10).map(|_| rng1.sample_interior(&capsule)).collect();
+        let b: Vec<Vec3> = (0..10).map(|_| rng2.sample_interior(&capsule)).collect();
+        assert_eq!(a, b);
+    }
+}
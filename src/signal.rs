@@ -0,0 +1,290 @@
+//! Smooth 1D noise signals for time-driven effects.
+//!
+//! A [`NoiseSignal`] is a pure function of its seed and the sample time, so
+//! camera shake, flicker and gusts replay exactly as long as the seed and the
+//! clock do. Signals are derived from an RNG's seed without consuming its
+//! state, so adding an effect doesn't change any other random outcome.
+
+use bevy::prelude::*;
+
+use crate::{EntityRng, GlobalRng, hash_combine};
+
+/// Stream id mixed into RNG seeds to derive signal seeds.
+const SIGNAL_STREAM: u64 = 0x5167_4A15;
+
+/// Interpolation scheme used by a [`NoiseSignal`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+pub enum SignalKind {
+    /// Smoothly interpolated random values. Lingers near its lattice values,
+    /// giving a slightly blobby, wandering feel.
+    Value,
+    /// Gradient (Perlin-style) noise. Crosses zero at every lattice point and
+    /// has no visible plateaus, which suits shake.
+    Gradient,
+}
+
+/// A seeded, smooth random signal over time with fractal octaves.
+///
+/// Samples lie in `[-1.0, 1.0]`. With one octave the signal changes direction
+/// about `frequency` times per second; every extra octave adds detail at
+/// `lacunarity` times the frequency and `persistence` times the amplitude.
+///
+/// # Examples
+///
+/// Trauma-based camera shake, where the entity's seed makes the shake
+/// identical in replays:
+///
+/// ```rust
+/// use bevy::prelude::*;
+/// use msg_rng::prelude::*;
+///
+/// #[derive(Component)]
+/// struct Trauma(f32);
+///
+/// fn shake(mut cameras: Query<(&mut Transform, &Trauma, &EntityRng)>, time: Res<Time>) {
+///     for (mut transform, trauma, rng) in &mut cameras {
+///         let signal = rng.signal(0).with_frequency(15.0).with_octaves(3);
+///         let strength = trauma.0 * trauma.0;
+///         let offset = signal.sample_vec2(time.elapsed_secs()) * strength * 0.5;
+///         let roll = signal.sample(time.elapsed_secs() + 100.0) * strength * 0.1;
+///         transform.translation = offset.extend(transform.translation.z);
+///         transform.rotation = Quat::from_rotation_z(roll);
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Reflect)]
+pub struct NoiseSignal {
+    seed: u64,
+    kind: SignalKind,
+    frequency: f32,
+    octaves: u32,
+    lacunarity: f32,
+    persistence: f32,
+}
+
+impl NoiseSignal {
+    /// Create a single-octave gradient signal at 1 Hz.
+    #[must_use]
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            kind: SignalKind::Gradient,
+            frequency: 1.0,
+            octaves: 1,
+            lacunarity: 2.0,
+            persistence: 0.5,
+        }
+    }
+
+    /// Set the interpolation scheme.
+    #[must_use]
+    pub fn with_kind(mut self, kind: SignalKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    /// Set the base frequency in lattice points per second.
+    #[must_use]
+    pub fn with_frequency(mut self, frequency: f32) -> Self {
+        self.frequency = frequency;
+        self
+    }
+
+    /// Set the number of octaves. Values below 1 are treated as 1.
+    #[must_use]
+    pub fn with_octaves(mut self, octaves: u32) -> Self {
+        self.octaves = octaves.max(1);
+        self
+    }
+
+    /// Set the frequency multiplier between octaves.
+    #[must_use]
+    pub fn with_lacunarity(mut self, lacunarity: f32) -> Self {
+        self.lacunarity = lacunarity;
+        self
+    }
+
+    /// Set the amplitude multiplier between octaves.
+    #[must_use]
+    pub fn with_persistence(mut self, persistence: f32) -> Self {
+        self.persistence = persistence;
+        self
+    }
+
+    /// Get the seed.
+    #[must_use]
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Sample the signal at time `t` in seconds.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn sample(&self, t: f32) -> f32 {
+        let mut frequency = f64::from(self.frequency);
+        let mut amplitude = 1.0;
+        let mut total = 0.0;
+        let mut norm = 0.0;
+        for octave in 0..self.octaves {
+            let seed = hash_combine(self.seed, u64::from(octave));
+            total += amplitude * self.octave(seed, f64::from(t) * frequency);
+            norm += amplitude;
+            frequency *= f64::from(self.lacunarity);
+            amplitude *= f64::from(self.persistence);
+        }
+        if norm > 0.0 {
+            (total / norm).clamp(-1.0, 1.0) as f32
+        } else {
+            0.0
+        }
+    }
+
+    /// Sample two independent channels at time `t`, e.g. for 2D shake.
+    #[must_use]
+    pub fn sample_vec2(&self, t: f32) -> Vec2 {
+        Vec2::new(self.channel(0).sample(t), self.channel(1).sample(t))
+    }
+
+    /// Sample three independent channels at time `t`, e.g. for 3D shake.
+    #[must_use]
+    pub fn sample_vec3(&self, t: f32) -> Vec3 {
+        Vec3::new(
+            self.channel(0).sample(t),
+            self.channel(1).sample(t),
+            self.channel(2).sample(t),
+        )
+    }
+
+    fn channel(&self, channel: u64) -> Self {
+        Self {
+            seed: hash_combine(self.seed, channel.wrapping_add(SIGNAL_STREAM)),
+            ..*self
+        }
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn octave(&self, seed: u64, x: f64) -> f64 {
+        let cell = x.floor();
+        let frac = x - cell;
+        let i = cell as i64;
+        let a = lattice(seed, i);
+        let b = lattice(seed, i.wrapping_add(1));
+        match self.kind {
+            SignalKind::Value => lerp(a, b, smoothstep(frac)),
+            // Slopes in [-1, 1] peak at ±0.5 between lattice points
+            SignalKind::Gradient => 2.0 * lerp(a * frac, b * (frac - 1.0), quintic(frac)),
+        }
+    }
+}
+
+/// Random value in `[-1.0, 1.0]` for lattice point `i`.
+#[allow(clippy::cast_precision_loss, clippy::cast_sign_loss)]
+fn lattice(seed: u64, i: i64) -> f64 {
+    let h = hash_combine(seed, i as u64);
+    (h >> 11) as f64 / (1u64 << 52) as f64 - 1.0
+}
+
+fn lerp(a: f64, b: f64, t: f64) -> f64 {
+    a + (b - a) * t
+}
+
+fn smoothstep(t: f64) -> f64 {
+    t * t * (3.0 - 2.0 * t)
+}
+
+fn quintic(t: f64) -> f64 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+impl GlobalRng {
+    /// Derive a [`NoiseSignal`] from the seed.
+    ///
+    /// Each `channel` gives an independent signal, e.g. one for shake and one
+    /// for flicker. This does not consume randomness from the RNG.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use msg_rng::GlobalRng;
+    /// # let rng = GlobalRng::seeded(42);
+    /// let wind = rng.signal(0).with_frequency(0.2).with_octaves(4);
+    /// let gust = wind.sample(12.5).max(0.0);
+    /// ```
+    #[must_use]
+    pub fn signal(&self, channel: u32) -> NoiseSignal {
+        NoiseSignal::new(hash_combine(self.seed(), SIGNAL_STREAM)).channel(u64::from(channel))
+    }
+}
+
+impl EntityRng {
+    /// Derive a [`NoiseSignal`] from this entity's seed.
+    ///
+    /// Each `channel` gives an independent signal. This does not consume
+    /// randomness from the RNG.
+    #[must_use]
+    pub fn signal(&self, channel: u32) -> NoiseSignal {
+        NoiseSignal::new(hash_combine(self.seed(), SIGNAL_STREAM)).channel(u64::from(channel))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn samples(signal: &NoiseSignal) -> impl Iterator<Item = f32> + '_ {
+        (0..10_000).map(|i| signal.sample(i as f32 * 0.01 - 20.0))
+    }
+
+    #[test]
+    fn samples_stay_in_range_and_vary() {
+        for kind in [SignalKind::Value, SignalKind::Gradient] {
+            let signal = NoiseSignal::new(1).with_kind(kind).with_octaves(4);
+            let (min, max) =
+                samples(&signal).fold((f32::MAX, f32::MIN), |(lo, hi), x| (lo.min(x), hi.max(x)));
+            assert!(min >= -1.0 && max <= 1.0, "{kind:?}: {min}..{max}");
+            assert!(max - min > 0.5, "{kind:?}: {min}..{max}");
+        }
+    }
+
+    #[test]
+    fn signal_is_continuous() {
+        let signal = NoiseSignal::new(2).with_frequency(3.0).with_octaves(3);
+        let values: Vec<f32> = samples(&signal).collect();
+        for pair in values.windows(2) {
+            assert!((pair[1] - pair[0]).abs() < 0.2, "{pair:?}");
+        }
+    }
+
+    #[test]
+    fn gradient_noise_is_zero_on_lattice() {
+        let signal = NoiseSignal::new(3);
+        for i in -5..5 {
+            assert!(signal.sample(i as f32).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn signals_follow_seeds_and_channels() {
+        let a = EntityRng::seeded(4);
+        let b = EntityRng::seeded(4);
+        let c = GlobalRng::seeded(4);
+
+        assert_eq!(a.signal(0), b.signal(0));
+        assert_eq!(a.signal(0), c.signal(0));
+        assert_ne!(a.signal(0), a.signal(1));
+        assert_ne!(a.signal(0), EntityRng::seeded(5).signal(0));
+
+        let shake = a.signal(0).sample_vec3(1.3);
+        assert_ne!(shake.x, shake.y);
+        assert_ne!(shake.y, shake.z);
+    }
+
+    #[test]
+    fn deriving_signals_does_not_consume_rng() {
+        let mut a = EntityRng::seeded(6);
+        let mut b = EntityRng::seeded(6);
+
+        let _ = a.signal(0);
+        assert_eq!(a.f64(), b.f64());
+    }
+}
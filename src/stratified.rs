@@ -0,0 +1,403 @@
+//! Evenly spread point sets for placement and sampling kernels.
+//!
+//! Independent uniform points clump and leave holes. The patterns here cover a
+//! region evenly while still looking irregular, and are cheaper than Poisson
+//! disk sampling when only the count matters, not a minimum distance.
+
+use bevy::math::bounding::Aabb3d;
+use bevy::prelude::*;
+use rand::{Rng, RngExt};
+
+use crate::{EntityRng, GlobalRng};
+
+/// How [`GlobalRng::points_in_rect`] and [`GlobalRng::points_in_aabb`] spread
+/// their points.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect)]
+pub enum SamplePattern {
+    /// One random point per cell of a grid shaped to the region. When the
+    /// count doesn't fill the grid, a random subset of cells is used.
+    JitteredGrid,
+    /// Every axis is split into `count` strata, each containing exactly one
+    /// point, with strata paired up randomly between axes.
+    LatinHypercube,
+    /// The Halton sequence with randomly permuted digits and a random toroidal
+    /// shift.
+    Halton,
+    /// The Sobol sequence with a random digital (XOR) shift. Most even for
+    /// power-of-two counts.
+    Sobol,
+}
+
+#[allow(
+    clippy::cast_precision_loss,
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss
+)]
+fn unit_points<R: Rng + ?Sized, const D: usize>(
+    rng: &mut R,
+    pattern: SamplePattern,
+    extent: [f32; D],
+    count: usize,
+) -> Vec<[f32; D]> {
+    match pattern {
+        SamplePattern::JitteredGrid => jittered_grid(rng, extent, count),
+        SamplePattern::LatinHypercube => {
+            let strata: [Vec<usize>; D] = std::array::from_fn(|_| {
+                let mut order: Vec<usize> = (0..count).collect();
+                rand::seq::SliceRandom::shuffle(order.as_mut_slice(), rng);
+                order
+            });
+            (0..count)
+                .map(|i| {
+                    std::array::from_fn(|axis| {
+                        (strata[axis][i] as f32 + rng.random::<f32>()) / count as f32
+                    })
+                })
+                .collect()
+        }
+        SamplePattern::Halton => {
+            const BASES: [u32; 3] = [2, 3, 5];
+            let digits: [Vec<u32>; D] = std::array::from_fn(|axis| {
+                // Zero stays fixed so trailing digits don't shift the point
+                let mut permutation: Vec<u32> = (0..BASES[axis]).collect();
+                rand::seq::SliceRandom::shuffle(&mut permutation[1..], rng);
+                permutation
+            });
+            let shift: [f32; D] = std::array::from_fn(|_| rng.random());
+            (0..count)
+                .map(|i| {
+                    std::array::from_fn(|axis| {
+                        let x = radical_inverse(i as u64, BASES[axis], &digits[axis]);
+                        (x + shift[axis]).fract()
+                    })
+                })
+                .collect()
+        }
+        SamplePattern::Sobol => {
+            let scramble: [u32; D] = std::array::from_fn(|_| rng.random());
+            let directions: [[u32; 32]; D] = std::array::from_fn(sobol_directions);
+            (0..count)
+                .map(|i| {
+                    std::array::from_fn(|axis| {
+                        let bits = sobol(i as u32, &directions[axis]) ^ scramble[axis];
+                        (bits >> 8) as f32 / (1u32 << 24) as f32
+                    })
+                })
+                .collect()
+        }
+    }
+}
+
+#[allow(
+    clippy::cast_precision_loss,
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss
+)]
+fn jittered_grid<R: Rng + ?Sized, const D: usize>(
+    rng: &mut R,
+    extent: [f32; D],
+    count: usize,
+) -> Vec<[f32; D]> {
+    if count == 0 {
+        return Vec::new();
+    }
+    // Size cells to be as close to cubes as possible
+    let volume: f32 = extent.iter().map(|e| e.max(f32::EPSILON)).product();
+    let cell = (volume / count as f32).powf(1.0 / D as f32);
+    let mut dims: [usize; D] =
+        std::array::from_fn(|axis| ((extent[axis] / cell).round() as usize).max(1));
+    // Rounding may leave too few cells; grow the axis with the largest cells
+    while dims.iter().product::<usize>() < count {
+        let axis = (0..D)
+            .max_by(|&a, &b| (extent[a] / dims[a] as f32).total_cmp(&(extent[b] / dims[b] as f32)))
+            .unwrap_or(0);
+        dims[axis] += 1;
+    }
+
+    let cells = dims.iter().product::<usize>();
+    let mut chosen = rand::seq::index::sample(rng, cells, count).into_vec();
+    chosen.sort_unstable();
+    chosen
+        .into_iter()
+        .map(|mut index| {
+            std::array::from_fn(|axis| {
+                let coord = index % dims[axis];
+                index /= dims[axis];
+                (coord as f32 + rng.random::<f32>()) / dims[axis] as f32
+            })
+        })
+        .collect()
+}
+
+/// Mirror the base-`base` digits of `index` around the radix point, mapping
+/// each through `digits`.
+#[allow(clippy::cast_precision_loss)]
+fn radical_inverse(mut index: u64, base: u32, digits: &[u32]) -> f32 {
+    let base = u64::from(base);
+    let inverse = 1.0 / base as f64;
+    let mut scale = inverse;
+    let mut result = 0.0;
+    while index > 0 {
+        result += f64::from(digits[(index % base) as usize]) * scale;
+        index /= base;
+        scale *= inverse;
+    }
+    #[allow(clippy::cast_possible_truncation)]
+    let result = result as f32;
+    result.min(1.0 - f32::EPSILON)
+}
+
+/// Direction numbers for the first three Sobol dimensions (Joe and Kuo).
+fn sobol_directions(dimension: usize) -> [u32; 32] {
+    let mut v = [0_u32; 32];
+    match dimension {
+        0 => {
+            for (k, v) in v.iter_mut().enumerate() {
+                *v = 1 << (31 - k);
+            }
+        }
+        _ => {
+            // Primitive polynomials x + 1 and x^2 + x + 1
+            let (s, a, m): (usize, u32, &[u32]) = if dimension == 1 {
+                (1, 0, &[1])
+            } else {
+                (2, 1, &[1, 3])
+            };
+            for k in 0..32 {
+                v[k] = if k < s {
+                    m[k] << (31 - k)
+                } else {
+                    let mut value = v[k - s] ^ (v[k - s] >> s);
+                    for l in 1..s {
+                        if (a >> (s - 1 - l)) & 1 == 1 {
+                            value ^= v[k - l];
+                        }
+                    }
+                    value
+                };
+            }
+        }
+    }
+    v
+}
+
+fn sobol(index: u32, directions: &[u32; 32]) -> u32 {
+    (0..32)
+        .filter(|bit| index >> bit & 1 == 1)
+        .fold(0, |x, bit| x ^ directions[bit as usize])
+}
+
+fn points_in_rect<R: Rng + ?Sized>(
+    rng: &mut R,
+    pattern: SamplePattern,
+    rect: Rect,
+    count: usize,
+) -> Vec<Vec2> {
+    let size = rect.size();
+    unit_points(rng, pattern, size.to_array(), count)
+        .into_iter()
+        .map(|p| rect.min + Vec2::from(p) * size)
+        .collect()
+}
+
+fn points_in_aabb<R: Rng + ?Sized>(
+    rng: &mut R,
+    pattern: SamplePattern,
+    aabb: Aabb3d,
+    count: usize,
+) -> Vec<Vec3> {
+    let (min, size) = (Vec3::from(aabb.min), Vec3::from(aabb.max - aabb.min));
+    unit_points(rng, pattern, size.to_array(), count)
+        .into_iter()
+        .map(|p| min + Vec3::from(p) * size)
+        .collect()
+}
+
+impl GlobalRng {
+    /// Generate `count` points spread evenly over `rect` by `pattern`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use bevy::prelude::*;
+    /// use msg_rng::SamplePattern;
+    /// # use msg_rng::GlobalRng;
+    /// # let mut rng = GlobalRng::seeded(42);
+    ///
+    /// let meadow = Rect::new(0.0, 0.0, 40.0, 20.0);
+    /// let tufts = rng.points_in_rect(SamplePattern::JitteredGrid, meadow, 200);
+    /// assert_eq!(tufts.len(), 200);
+    /// assert!(tufts.iter().all(|&p| meadow.contains(p)));
+    /// ```
+    pub fn points_in_rect(
+        &mut self,
+        pattern: SamplePattern,
+        rect: Rect,
+        count: usize,
+    ) -> Vec<Vec2> {
+        points_in_rect(&mut self.rng, pattern, rect, count)
+    }
+
+    /// Generate `count` points spread evenly over `aabb` by `pattern`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use bevy::math::bounding::Aabb3d;
+    /// use bevy::prelude::*;
+    /// use msg_rng::SamplePattern;
+    /// # use msg_rng::GlobalRng;
+    /// # let mut rng = GlobalRng::seeded(42);
+    ///
+    /// // An ambient occlusion kernel in the unit cube
+    /// let cube = Aabb3d::new(Vec3::ZERO, Vec3::ONE);
+    /// let kernel = rng.points_in_aabb(SamplePattern::Sobol, cube, 64);
+    /// ```
+    pub fn points_in_aabb(
+        &mut self,
+        pattern: SamplePattern,
+        aabb: Aabb3d,
+        count: usize,
+    ) -> Vec<Vec3> {
+        points_in_aabb(&mut self.rng, pattern, aabb, count)
+    }
+}
+
+impl EntityRng {
+    /// Generate `count` points spread evenly over `rect` by `pattern`.
+    pub fn points_in_rect(
+        &mut self,
+        pattern: SamplePattern,
+        rect: Rect,
+        count: usize,
+    ) -> Vec<Vec2> {
+        points_in_rect(&mut self.rng, pattern, rect, count)
+    }
+
+    /// Generate `count` points spread evenly over `aabb` by `pattern`.
+    pub fn points_in_aabb(
+        &mut self,
+        pattern: SamplePattern,
+        aabb: Aabb3d,
+        count: usize,
+    ) -> Vec<Vec3> {
+        points_in_aabb(&mut self.rng, pattern, aabb, count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PATTERNS: [SamplePattern; 4] = [
+        SamplePattern::JitteredGrid,
+        SamplePattern::LatinHypercube,
+        SamplePattern::Halton,
+        SamplePattern::Sobol,
+    ];
+
+    /// Count points per cell of an `n` by `n` grid over the unit square.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn histogram(points: &[Vec2], n: usize) -> Vec<usize> {
+        let mut bins = vec![0; n * n];
+        for p in points {
+            let x = ((p.x * n as f32) as usize).min(n - 1);
+            let y = ((p.y * n as f32) as usize).min(n - 1);
+            bins[y * n + x] += 1;
+        }
+        bins
+    }
+
+    #[test]
+    fn patterns_cover_the_region_evenly() {
+        let mut rng = GlobalRng::seeded(1);
+        let unit = Rect::new(0.0, 0.0, 1.0, 1.0);
+        // Latin hypercubes are only stratified per axis, see below
+        for pattern in [
+            SamplePattern::JitteredGrid,
+            SamplePattern::Halton,
+            SamplePattern::Sobol,
+        ] {
+            let points = rng.points_in_rect(pattern, unit, 1024);
+            assert_eq!(points.len(), 1024);
+            assert!(points.iter().all(|&p| unit.contains(p)));
+            // 64 per bin on average; independent points would vary by ±8
+            for count in histogram(&points, 4) {
+                assert!((58..=70).contains(&count), "{pattern:?}: {count}");
+            }
+        }
+    }
+
+    #[test]
+    fn jittered_grid_fills_distinct_cells() {
+        let mut rng = EntityRng::seeded(2);
+        let rect = Rect::new(-10.0, 0.0, 10.0, 5.0);
+        let points = rng.points_in_rect(SamplePattern::JitteredGrid, rect, 64);
+
+        // 64 points over a 4:1 region form a 16 by 4 grid
+        let mut cells: Vec<(i32, i32)> = points
+            .iter()
+            .map(|p| {
+                (
+                    ((p.x + 10.0) / 1.25).floor() as i32,
+                    (p.y / 1.25).floor() as i32,
+                )
+            })
+            .collect();
+        cells.sort_unstable();
+        cells.dedup();
+        assert_eq!(cells.len(), 64);
+
+        // Counts that don't fill a grid still give exactly that many points
+        let uneven = rng.points_in_rect(SamplePattern::JitteredGrid, rect, 7);
+        assert_eq!(uneven.len(), 7);
+    }
+
+    #[test]
+    fn latin_hypercube_has_one_point_per_stratum() {
+        let mut rng = GlobalRng::seeded(3);
+        let cube = Aabb3d::new(Vec3::splat(5.0), Vec3::splat(5.0));
+        let points = rng.points_in_aabb(SamplePattern::LatinHypercube, cube, 50);
+
+        for axis in 0..3 {
+            let mut strata: Vec<usize> = points
+                .iter()
+                .map(|p| (p[axis] / 10.0 * 50.0) as usize)
+                .collect();
+            strata.sort_unstable();
+            assert_eq!(strata, (0..50).collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn sobol_matches_reference_sequence() {
+        let directions: [[u32; 32]; 3] = std::array::from_fn(sobol_directions);
+        let points: Vec<[f32; 3]> = (0..4)
+            .map(|i| {
+                std::array::from_fn(|axis| sobol(i, &directions[axis]) as f32 / 4_294_967_296.0)
+            })
+            .collect();
+        assert_eq!(
+            points,
+            [
+                [0.0, 0.0, 0.0],
+                [0.5, 0.5, 0.5],
+                [0.25, 0.75, 0.75],
+                [0.75, 0.25, 0.25]
+            ]
+        );
+        assert_eq!(radical_inverse(6, 2, &[0, 1]), 0.375);
+    }
+
+    #[test]
+    fn patterns_are_reproducible() {
+        let rect = Rect::new(0.0, 0.0, 3.0, 2.0);
+        for pattern in PATTERNS {
+            let a = GlobalRng::seeded(4).points_in_rect(pattern, rect, 20);
+            let b = EntityRng::seeded(4).points_in_rect(pattern, rect, 20);
+            let c = GlobalRng::seeded(5).points_in_rect(pattern, rect, 20);
+            assert_eq!(a, b);
+            assert_ne!(a, c, "{pattern:?}");
+        }
+    }
+}
@@ -0,0 +1,386 @@
+//! Timers with randomized intervals.
+//!
+//! [`RandomTimer`] behaves like Bevy's `Timer`, except every interval is drawn
+//! fresh from a [`TimerInterval`] distribution using the entity's
+//! [`EntityRng`]. Add [`RandomTimerPlugin`] to tick them automatically.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use rand::RngExt;
+
+use crate::distributions::{exponential, normal};
+use crate::{EntityRng, RngSource};
+
+/// Shortest interval a [`RandomTimer`] will wait, so distributions that can
+/// produce zero or negative values can't fire endlessly within one tick.
+const MIN_INTERVAL: f32 = 0.001;
+
+/// How the time between [`RandomTimer`] firings is chosen, in seconds.
+#[derive(Debug, Clone, Copy, PartialEq, Reflect)]
+pub enum TimerInterval {
+    /// Uniformly between `min` and `max`.
+    Uniform {
+        /// Shortest interval.
+        min: f32,
+        /// Longest interval.
+        max: f32,
+    },
+    /// Exponentially distributed with the given mean, so firings form a
+    /// Poisson process: the chance of firing soon never depends on how long
+    /// the timer has already waited.
+    Exponential {
+        /// Average interval.
+        mean: f32,
+    },
+    /// Normally distributed around `mean`.
+    Normal {
+        /// Average interval.
+        mean: f32,
+        /// Standard deviation of the interval.
+        std_dev: f32,
+    },
+}
+
+impl TimerInterval {
+    fn is_valid(self) -> bool {
+        match self {
+            Self::Uniform { min, max } => min.is_finite() && max.is_finite() && min <= max,
+            Self::Exponential { mean } => mean.is_finite() && mean > 0.0,
+            Self::Normal { mean, std_dev } => {
+                mean.is_finite() && std_dev.is_finite() && std_dev >= 0.0
+            }
+        }
+    }
+
+    /// Draw an interval in seconds.
+    #[allow(clippy::cast_possible_truncation)]
+    fn draw<R: RngSource + ?Sized>(self, rng: &mut R) -> f32 {
+        let rng = rng.rng_mut();
+        let seconds = match self {
+            Self::Uniform { min, max } => rng.random_range(min..=max),
+            Self::Exponential { mean } => exponential(rng, 1.0 / f64::from(mean)) as f32,
+            Self::Normal { mean, std_dev } => {
+                normal(rng, f64::from(mean), f64::from(std_dev)) as f32
+            }
+        };
+        seconds.max(MIN_INTERVAL)
+    }
+}
+
+/// A timer that fires after randomly drawn intervals.
+///
+/// The first interval is drawn on the first tick, so spawning many timers at
+/// once with the same [`TimerInterval`] still spreads their firings out.
+///
+/// With [`RandomTimerPlugin`], entities that have both a `RandomTimer` and an
+/// [`EntityRng`] are ticked every frame and send [`RandomTimerFinished`].
+///
+/// # Examples
+///
+/// ```rust
+/// use bevy::prelude::*;
+/// use msg_rng::prelude::*;
+/// use msg_rng::{RandomTimer, TimerInterval};
+///
+/// fn spawn_villager(mut commands: Commands, rng: Res<GlobalRng>) {
+///     commands.spawn((
+///         EntityRng::from_global(&rng),
+///         // Idle bark every 5–15 seconds
+///         RandomTimer::repeating(TimerInterval::Uniform { min: 5.0, max: 15.0 }),
+///     ));
+/// }
+///
+/// fn bark(mut timers: Query<(&mut RandomTimer, &mut EntityRng)>, time: Res<Time>) {
+///     for (mut timer, mut rng) in &mut timers {
+///         if timer.tick(time.delta(), &mut rng).just_finished() {
+///             println!("Lovely weather!");
+///         }
+///     }
+/// }
+/// ```
+#[derive(Component, Debug, Clone, PartialEq, Reflect)]
+pub struct RandomTimer {
+    interval: TimerInterval,
+    mode: TimerMode,
+    /// Seconds until the next firing, or `None` before the first draw
+    remaining: Option<f32>,
+    paused: bool,
+    finished: bool,
+    times_finished_this_tick: u32,
+}
+
+impl RandomTimer {
+    /// Create a timer with the given interval distribution and mode.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the interval parameters are not finite, if a uniform range
+    /// has `min > max`, if an exponential mean is not positive, or if a
+    /// normal standard deviation is negative.
+    #[must_use]
+    pub fn new(interval: TimerInterval, mode: TimerMode) -> Self {
+        assert!(
+            interval.is_valid(),
+            "invalid random timer interval: {interval:?}"
+        );
+        Self {
+            interval,
+            mode,
+            remaining: None,
+            paused: false,
+            finished: false,
+            times_finished_this_tick: 0,
+        }
+    }
+
+    /// Create a timer that keeps firing, drawing a new interval each time.
+    ///
+    /// # Panics
+    ///
+    /// See [`RandomTimer::new`].
+    #[must_use]
+    pub fn repeating(interval: TimerInterval) -> Self {
+        Self::new(interval, TimerMode::Repeating)
+    }
+
+    /// Create a timer that fires once after a random delay.
+    ///
+    /// # Panics
+    ///
+    /// See [`RandomTimer::new`].
+    #[must_use]
+    pub fn once(interval: TimerInterval) -> Self {
+        Self::new(interval, TimerMode::Once)
+    }
+
+    /// Get the interval distribution.
+    #[must_use]
+    pub fn interval(&self) -> TimerInterval {
+        self.interval
+    }
+
+    /// Get the timer mode.
+    #[must_use]
+    pub fn mode(&self) -> TimerMode {
+        self.mode
+    }
+
+    /// Advance the timer by `delta`, drawing new intervals from `rng` as needed.
+    ///
+    /// A repeating timer can fire several times in one tick if `delta` spans
+    /// multiple intervals; see [`RandomTimer::times_finished_this_tick`].
+    pub fn tick<R: RngSource + ?Sized>(&mut self, delta: Duration, rng: &mut R) -> &Self {
+        self.times_finished_this_tick = 0;
+        if self.paused || self.finished {
+            return self;
+        }
+
+        let mut remaining = match self.remaining {
+            Some(remaining) => remaining,
+            None => self.interval.draw(rng),
+        };
+        let mut delta = delta.as_secs_f32();
+        while delta >= remaining {
+            delta -= remaining;
+            self.times_finished_this_tick += 1;
+            if self.mode == TimerMode::Once {
+                self.finished = true;
+                self.remaining = Some(0.0);
+                return self;
+            }
+            remaining = self.interval.draw(rng);
+        }
+        self.remaining = Some(remaining - delta);
+        self
+    }
+
+    /// Check if the timer fired during the last tick.
+    #[must_use]
+    pub fn just_finished(&self) -> bool {
+        self.times_finished_this_tick > 0
+    }
+
+    /// Get how many times the timer fired during the last tick.
+    #[must_use]
+    pub fn times_finished_this_tick(&self) -> u32 {
+        self.times_finished_this_tick
+    }
+
+    /// Check if a [`TimerMode::Once`] timer has fired. Always `false` for
+    /// repeating timers.
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Get the time until the next firing, or `None` if no interval has been
+    /// drawn yet.
+    #[must_use]
+    pub fn remaining(&self) -> Option<Duration> {
+        self.remaining.map(Duration::from_secs_f32)
+    }
+
+    /// Pause the timer.
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    /// Resume a paused timer.
+    pub fn unpause(&mut self) {
+        self.paused = false;
+    }
+
+    /// Check if the timer is paused.
+    #[must_use]
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Restart the timer; a fresh interval is drawn on the next tick.
+    pub fn reset(&mut self) {
+        self.remaining = None;
+        self.finished = false;
+        self.times_finished_this_tick = 0;
+    }
+}
+
+/// Sent by [`RandomTimerPlugin`] each time a [`RandomTimer`] fires.
+///
+/// It is written as a message for [`MessageReader`]s and also triggered on the
+/// timer's entity for observers.
+#[derive(Message, EntityEvent, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RandomTimerFinished {
+    /// The entity whose timer fired.
+    pub entity: Entity,
+}
+
+/// Plugin that ticks every [`RandomTimer`] with an [`EntityRng`] in `Update`
+/// using the default [`Time`] clock.
+///
+/// Requires Bevy's `TimePlugin`, which is part of `MinimalPlugins` and
+/// `DefaultPlugins`.
+///
+/// # Examples
+///
+/// ```rust
+/// use bevy::prelude::*;
+/// use msg_rng::prelude::*;
+/// use msg_rng::{RandomTimer, RandomTimerFinished, RandomTimerPlugin, TimerInterval};
+///
+/// App::new()
+///     .add_plugins((MinimalPlugins, RngPlugin::seeded(42), RandomTimerPlugin))
+///     .add_observer(|finished: On<RandomTimerFinished>| {
+///         println!("{} blinked", finished.entity);
+///     });
+/// ```
+pub struct RandomTimerPlugin;
+
+impl Plugin for RandomTimerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_message::<RandomTimerFinished>()
+            .add_systems(Update, tick_random_timers);
+    }
+}
+
+fn tick_random_timers(
+    time: Res<Time>,
+    mut timers: Query<(Entity, &mut RandomTimer, &mut EntityRng)>,
+    mut finished: MessageWriter<RandomTimerFinished>,
+    mut commands: Commands,
+) {
+    for (entity, mut timer, mut rng) in &mut timers {
+        for _ in 0..timer
+            .tick(time.delta(), &mut rng)
+            .times_finished_this_tick()
+        {
+            finished.write(RandomTimerFinished { entity });
+            commands.trigger(RandomTimerFinished { entity });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FRAME: Duration = Duration::from_millis(10);
+
+    #[test]
+    fn uniform_intervals_stay_in_range() {
+        let mut timer = RandomTimer::repeating(TimerInterval::Uniform { min: 0.5, max: 1.5 });
+        let mut rng = EntityRng::seeded(1);
+        let mut since_last = 0;
+        let mut fired = 0;
+
+        for _ in 0..10_000 {
+            since_last += 1;
+            if timer.tick(FRAME, &mut rng).just_finished() {
+                assert!((50..=151).contains(&since_last), "{since_last}");
+                since_last = 0;
+                fired += 1;
+            }
+        }
+        assert!(fired > 60, "{fired}");
+    }
+
+    #[test]
+    fn exponential_intervals_match_mean() {
+        let mut timer = RandomTimer::repeating(TimerInterval::Exponential { mean: 0.1 });
+        let mut rng = EntityRng::seeded(2);
+
+        // 100 seconds at a mean of 0.1 seconds should fire about 1000 times
+        let fired: u32 = (0..10_000)
+            .map(|_| timer.tick(FRAME, &mut rng).times_finished_this_tick())
+            .sum();
+        assert!((900..1100).contains(&fired), "{fired}");
+    }
+
+    #[test]
+    fn once_timer_fires_once() {
+        let mut timer = RandomTimer::once(TimerInterval::Normal {
+            mean: 0.2,
+            std_dev: 0.05,
+        });
+        let mut rng = EntityRng::seeded(3);
+
+        let fired: u32 = (0..100)
+            .map(|_| timer.tick(FRAME, &mut rng).times_finished_this_tick())
+            .sum();
+        assert_eq!(fired, 1);
+        assert!(timer.is_finished());
+
+        timer.reset();
+        assert!(!timer.is_finished());
+        assert_eq!(timer.remaining(), None);
+    }
+
+    #[test]
+    fn large_deltas_fire_multiple_times() {
+        let mut timer = RandomTimer::repeating(TimerInterval::Uniform { min: 1.0, max: 1.0 });
+        let mut rng = EntityRng::seeded(4);
+
+        timer.tick(Duration::from_millis(3500), &mut rng);
+        assert_eq!(timer.times_finished_this_tick(), 3);
+        assert!((timer.remaining().unwrap().as_secs_f32() - 0.5).abs() < 1e-4);
+    }
+
+    #[test]
+    fn paused_timer_does_not_advance() {
+        let mut timer = RandomTimer::repeating(TimerInterval::Uniform { min: 1.0, max: 1.0 });
+        let mut rng = EntityRng::seeded(5);
+
+        timer.pause();
+        assert!(!timer.tick(Duration::from_secs(5), &mut rng).just_finished());
+        assert_eq!(timer.remaining(), None);
+
+        timer.unpause();
+        assert!(timer.tick(Duration::from_secs(1), &mut rng).just_finished());
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid random timer interval")]
+    fn invalid_interval_panics() {
+        let _ = RandomTimer::repeating(TimerInterval::Exponential { mean: 0.0 });
+    }
+}
@@ -0,0 +1,309 @@
+//! Uniform random points in common 2D and 3D regions.
+//!
+//! Every method samples uniformly by area or volume using closed-form
+//! transforms, so no samples are rejected and points don't bunch up in the
+//! middle of disks and spheres.
+
+use std::f32::consts::TAU;
+
+use bevy::math::bounding::Aabb3d;
+use bevy::prelude::*;
+use rand::{Rng, RngExt};
+
+use crate::{EntityRng, GlobalRng};
+
+fn check_radius(radius: f32) {
+    assert!(
+        radius >= 0.0 && radius.is_finite(),
+        "radius must be non-negative and finite"
+    );
+}
+
+fn vec2_on_circle<R: Rng + ?Sized>(rng: &mut R, radius: f32) -> Vec2 {
+    check_radius(radius);
+    Vec2::from_angle(rng.random_range(0.0..TAU)) * radius
+}
+
+fn vec2_in_annulus<R: Rng + ?Sized>(rng: &mut R, inner: f32, outer: f32) -> Vec2 {
+    check_radius(inner);
+    check_radius(outer);
+    assert!(inner <= outer, "inner radius must not exceed outer radius");
+
+    // Area grows with r², so invert the CDF of r² rather than r
+    let (inner2, outer2) = (inner * inner, outer * outer);
+    let radius = (inner2 + rng.random::<f32>() * (outer2 - inner2)).sqrt();
+    Vec2::from_angle(rng.random_range(0.0..TAU)) * radius
+}
+
+pub(crate) fn vec3_on_sphere<R: Rng + ?Sized>(rng: &mut R, radius: f32) -> Vec3 {
+    check_radius(radius);
+    // By Archimedes' hat-box theorem, z is uniform on a sphere's surface
+    let z = rng.random_range(-1.0..=1.0_f32);
+    let ring = (1.0 - z * z).max(0.0).sqrt();
+    let (sin, cos) = rng.random_range(0.0..TAU).sin_cos();
+    Vec3::new(ring * cos, ring * sin, z) * radius
+}
+
+fn vec3_in_sphere<R: Rng + ?Sized>(rng: &mut R, radius: f32) -> Vec3 {
+    check_radius(radius);
+    let direction = vec3_on_sphere(rng, 1.0);
+    direction * radius * rng.random::<f32>().cbrt()
+}
+
+fn vec2_in_rect<R: Rng + ?Sized>(rng: &mut R, rect: Rect) -> Vec2 {
+    rect.min + rect.size() * Vec2::new(rng.random(), rng.random())
+}
+
+fn vec3_in_aabb<R: Rng + ?Sized>(rng: &mut R, aabb: Aabb3d) -> Vec3 {
+    let (min, max) = (Vec3::from(aabb.min), Vec3::from(aabb.max));
+    min + (max - min) * Vec3::new(rng.random(), rng.random(), rng.random())
+}
+
+impl GlobalRng {
+    /// Generate a point uniformly distributed inside a circle of `radius`
+    /// centered on the origin.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `radius` is negative or not finite.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use bevy::prelude::*;
+    /// # use msg_rng::GlobalRng;
+    /// # let mut rng = GlobalRng::seeded(42);
+    ///
+    /// let camp = Vec2::new(100.0, 50.0);
+    /// let tent = camp + rng.vec2_in_circle(20.0);
+    /// assert!(tent.distance(camp) < 20.001);
+    /// ```
+    pub fn vec2_in_circle(&mut self, radius: f32) -> Vec2 {
+        vec2_in_annulus(&mut self.rng, 0.0, radius)
+    }
+
+    /// Generate a point uniformly distributed on a circle of `radius`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `radius` is negative or not finite.
+    pub fn vec2_on_circle(&mut self, radius: f32) -> Vec2 {
+        vec2_on_circle(&mut self.rng, radius)
+    }
+
+    /// Generate a point uniformly distributed in the ring between `inner` and
+    /// `outer` radius, e.g. to spawn enemies off-screen but not too far away.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either radius is negative or not finite, or if
+    /// `inner > outer`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use msg_rng::GlobalRng;
+    /// # let mut rng = GlobalRng::seeded(42);
+    /// let offset = rng.vec2_in_annulus(30.0, 40.0);
+    /// assert!((29.999..40.001).contains(&offset.length()));
+    /// ```
+    pub fn vec2_in_annulus(&mut self, inner: f32, outer: f32) -> Vec2 {
+        vec2_in_annulus(&mut self.rng, inner, outer)
+    }
+
+    /// Generate a point uniformly distributed inside a sphere of `radius`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `radius` is negative or not finite.
+    pub fn vec3_in_sphere(&mut self, radius: f32) -> Vec3 {
+        vec3_in_sphere(&mut self.rng, radius)
+    }
+
+    /// Generate a point uniformly distributed on the surface of a sphere of
+    /// `radius`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `radius` is negative or not finite.
+    pub fn vec3_on_sphere(&mut self, radius: f32) -> Vec3 {
+        vec3_on_sphere(&mut self.rng, radius)
+    }
+
+    /// Generate a point uniformly distributed inside `rect`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use bevy::prelude::*;
+    /// # use msg_rng::GlobalRng;
+    /// # let mut rng = GlobalRng::seeded(42);
+    ///
+    /// let arena = Rect::new(-50.0, -30.0, 50.0, 30.0);
+    /// assert!(arena.contains(rng.vec2_in_rect(arena)));
+    /// ```
+    pub fn vec2_in_rect(&mut self, rect: Rect) -> Vec2 {
+        vec2_in_rect(&mut self.rng, rect)
+    }
+
+    /// Generate a point uniformly distributed inside `aabb`.
+    pub fn vec3_in_aabb(&mut self, aabb: Aabb3d) -> Vec3 {
+        vec3_in_aabb(&mut self.rng, aabb)
+    }
+}
+
+impl EntityRng {
+    /// Generate a point uniformly distributed inside a circle of `radius`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `radius` is negative or not finite.
+    pub fn vec2_in_circle(&mut self, radius: f32) -> Vec2 {
+        vec2_in_annulus(&mut self.rng, 0.0, radius)
+    }
+
+    /// Generate a point uniformly distributed on a circle of `radius`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `radius` is negative or not finite.
+    pub fn vec2_on_circle(&mut self, radius: f32) -> Vec2 {
+        vec2_on_circle(&mut self.rng, radius)
+    }
+
+    /// Generate a point uniformly distributed in the ring between `inner` and
+    /// `outer` radius.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either radius is negative or not finite, or if
+    /// `inner > outer`.
+    pub fn vec2_in_annulus(&mut self, inner: f32, outer: f32) -> Vec2 {
+        vec2_in_annulus(&mut self.rng, inner, outer)
+    }
+
+    /// Generate a point uniformly distributed inside a sphere of `radius`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `radius` is negative or not finite.
+    pub fn vec3_in_sphere(&mut self, radius: f32) -> Vec3 {
+        vec3_in_sphere(&mut self.rng, radius)
+    }
+
+    /// Generate a point uniformly distributed on the surface of a sphere of
+    /// `radius`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `radius` is negative or not finite.
+    pub fn vec3_on_sphere(&mut self, radius: f32) -> Vec3 {
+        vec3_on_sphere(&mut self.rng, radius)
+    }
+
+    /// Generate a point uniformly distributed inside `rect`.
+    pub fn vec2_in_rect(&mut self, rect: Rect) -> Vec2 {
+        vec2_in_rect(&mut self.rng, rect)
+    }
+
+    /// Generate a point uniformly distributed inside `aabb`.
+    pub fn vec3_in_aabb(&mut self, aabb: Aabb3d) -> Vec3 {
+        vec3_in_aabb(&mut self.rng, aabb)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const N: usize = 20_000;
+
+    #[allow(clippy::cast_precision_loss)]
+    fn fraction(count: usize) -> f32 {
+        count as f32 / N as f32
+    }
+
+    #[test]
+    fn circle_points_are_uniform_by_area() {
+        let mut rng = GlobalRng::seeded(1);
+        let points: Vec<Vec2> = (0..N).map(|_| rng.vec2_in_circle(2.0)).collect();
+
+        assert!(points.iter().all(|p| p.length() <= 2.0 + 1e-5));
+        // The inner half radius covers a quarter of the area
+        let inner = points.iter().filter(|p| p.length() < 1.0).count();
+        assert!(
+            (fraction(inner) - 0.25).abs() < 0.015,
+            "{}",
+            fraction(inner)
+        );
+        // Each quadrant gets a quarter of the points
+        let right_top = points.iter().filter(|p| p.x > 0.0 && p.y > 0.0).count();
+        assert!((fraction(right_top) - 0.25).abs() < 0.015);
+
+        let on = rng.vec2_on_circle(3.0);
+        assert!((on.length() - 3.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn annulus_points_are_uniform_by_area() {
+        let mut rng = EntityRng::seeded(2);
+        let points: Vec<Vec2> = (0..N).map(|_| rng.vec2_in_annulus(1.0, 3.0)).collect();
+
+        assert!(
+            points
+                .iter()
+                .all(|p| (1.0 - 1e-5..=3.0 + 1e-5).contains(&p.length()))
+        );
+        // Ring 1..2 has area 3π out of the total 8π
+        let inner = points.iter().filter(|p| p.length() < 2.0).count();
+        assert!((fraction(inner) - 3.0 / 8.0).abs() < 0.015);
+    }
+
+    #[test]
+    fn sphere_points_are_uniform_by_volume_and_area() {
+        let mut rng = GlobalRng::seeded(3);
+
+        let inside: Vec<Vec3> = (0..N).map(|_| rng.vec3_in_sphere(2.0)).collect();
+        assert!(inside.iter().all(|p| p.length() <= 2.0 + 1e-5));
+        // The inner half radius covers an eighth of the volume
+        let inner = inside.iter().filter(|p| p.length() < 1.0).count();
+        assert!((fraction(inner) - 0.125).abs() < 0.01);
+
+        let surface: Vec<Vec3> = (0..N).map(|_| rng.vec3_on_sphere(1.0)).collect();
+        assert!(surface.iter().all(|p| (p.length() - 1.0).abs() < 1e-4));
+        // A band of height 0.5 covers a quarter of the surface
+        let band = surface.iter().filter(|p| p.z.abs() < 0.25).count();
+        assert!((fraction(band) - 0.25).abs() < 0.015);
+    }
+
+    #[test]
+    fn box_points_stay_inside() {
+        let mut rng = EntityRng::seeded(4);
+        let rect = Rect::new(-1.0, 2.0, 3.0, 5.0);
+        let aabb = Aabb3d::new(Vec3::new(1.0, 2.0, 3.0), Vec3::new(0.5, 1.0, 2.0));
+
+        for _ in 0..1000 {
+            assert!(rect.contains(rng.vec2_in_rect(rect)));
+            let p = rng.vec3_in_aabb(aabb);
+            assert!(p.cmpge(aabb.min.into()).all() && p.cmple(aabb.max.into()).all());
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "inner radius must not exceed outer radius")]
+    fn inverted_annulus_panics() {
+        let _ = GlobalRng::seeded(5).vec2_in_annulus(2.0, 1.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "radius must be non-negative and finite")]
+    fn negative_sphere_radius_panics() {
+        let _ = GlobalRng::seeded(6).vec3_in_sphere(-1.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "radius must be non-negative and finite")]
+    fn nan_sphere_radius_panics() {
+        let _ = EntityRng::seeded(7).vec3_in_sphere(f32::NAN);
+    }
+}
@@ -0,0 +1,279 @@
+//! Random walks on a grid for caves, rivers and roads.
+//!
+//! Walkers step between orthogonally adjacent cells, optionally pulled toward
+//! a target. Several walkers sharing a carve limit give the classic
+//! "drunkard's walk" cave, while a single biased walker traces a meandering
+//! river or road.
+
+use bevy::prelude::*;
+use rand::RngExt;
+use rand::rngs::StdRng;
+use rand::seq::IndexedRandom;
+
+use crate::grid::in_bounds;
+use crate::{Grid, RngSource};
+
+const DIRECTIONS: [IVec2; 4] = [IVec2::X, IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y];
+
+/// Random walk generator on a 2D grid.
+///
+/// Walkers take up to [`with_steps`](Self::with_steps) steps each, never
+/// leaving the grid. A walker stops early when it reaches the target, and all
+/// walkers stop once the carve limit is reached.
+///
+/// # Examples
+///
+/// ```rust
+/// use bevy::prelude::*;
+/// use msg_rng::prelude::*;
+/// use msg_rng::RandomWalk;
+///
+/// let mut rng = GlobalRng::seeded(42);
+/// let size = UVec2::new(64, 48);
+///
+/// // A drunkard's walk cave: 4 walkers open up 40% of the map
+/// let cave = RandomWalk::new(size, IVec2::new(32, 24))
+///     .with_walkers(4)
+///     .with_carve_limit(64 * 48 * 2 / 5)
+///     .carve(&mut rng);
+/// assert_eq!(cave.count(|&open| open), 64 * 48 * 2 / 5);
+///
+/// // A river meandering from the top edge toward the bottom edge
+/// let river = RandomWalk::new(size, IVec2::new(10, 47))
+///     .with_target(IVec2::new(50, 0), 0.6)
+///     .paths(&mut rng)
+///     .remove(0);
+/// assert_eq!(river.last(), Some(&IVec2::new(50, 0)));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct RandomWalk {
+    size: UVec2,
+    start: IVec2,
+    steps: u32,
+    walkers: u32,
+    target: Option<(IVec2, f32)>,
+    carve_limit: Option<usize>,
+}
+
+impl RandomWalk {
+    /// Create a single walker starting at `start` on a grid of `size` cells.
+    ///
+    /// It takes one step per cell of the grid unless changed with
+    /// [`with_steps`](Self::with_steps).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `start` is outside the grid.
+    #[must_use]
+    pub fn new(size: UVec2, start: IVec2) -> Self {
+        check_inside(size, start, "start");
+        Self {
+            size,
+            start,
+            steps: size.element_product(),
+            walkers: 1,
+            target: None,
+            carve_limit: None,
+        }
+    }
+
+    /// Set the maximum number of steps each walker takes.
+    #[must_use]
+    pub fn with_steps(mut self, steps: u32) -> Self {
+        self.steps = steps;
+        self
+    }
+
+    /// Set how many walkers start together. They take turns stepping, so a
+    /// carve limit is shared evenly between them.
+    #[must_use]
+    pub fn with_walkers(mut self, walkers: u32) -> Self {
+        self.walkers = walkers.max(1);
+        self
+    }
+
+    /// Pull walkers toward `target`, stopping each walker once it arrives.
+    ///
+    /// With probability `bias` a step moves closer to the target; otherwise
+    /// it goes in a random direction. A `bias` of `0.0` wanders aimlessly
+    /// until it happens to arrive, while `1.0` walks straight there along a
+    /// random staircase.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `target` is outside the grid or `bias` is not in
+    /// `[0.0, 1.0]`.
+    #[must_use]
+    pub fn with_target(mut self, target: IVec2, bias: f32) -> Self {
+        check_inside(self.size, target, "target");
+        assert!((0.0..=1.0).contains(&bias), "bias must be in [0.0, 1.0]");
+        self.target = Some((target, bias));
+        self
+    }
+
+    /// Stop all walkers once `cells` distinct cells have been visited,
+    /// including the start.
+    #[must_use]
+    pub fn with_carve_limit(mut self, cells: usize) -> Self {
+        self.carve_limit = Some(cells);
+        self
+    }
+
+    /// Walk and return each walker's path, starting with the start cell.
+    ///
+    /// Consecutive cells in a path are always orthogonal neighbors.
+    pub fn paths<R: RngSource + ?Sized>(&self, rng: &mut R) -> Vec<Vec<IVec2>> {
+        self.run(rng.rng_mut()).0
+    }
+
+    /// Walk and return the grid of visited cells.
+    pub fn carve<R: RngSource + ?Sized>(&self, rng: &mut R) -> Grid<bool> {
+        self.run(rng.rng_mut()).1
+    }
+
+    fn run(&self, rng: &mut StdRng) -> (Vec<Vec<IVec2>>, Grid<bool>) {
+        let mut carved = Grid::new(self.size, false);
+        carved[self.start] = true;
+        let mut remaining = self.carve_limit.map(|limit| limit.saturating_sub(1));
+        let mut paths = vec![vec![self.start]; self.walkers as usize];
+        let mut done = vec![self.target.is_some_and(|(t, _)| t == self.start); paths.len()];
+
+        for _ in 0..self.steps {
+            if done.iter().all(|&d| d) || remaining == Some(0) {
+                break;
+            }
+            for (path, done) in paths.iter_mut().zip(&mut done) {
+                if *done {
+                    continue;
+                }
+                let Some(next) = self.step(rng, path[path.len() - 1]) else {
+                    *done = true;
+                    continue;
+                };
+                path.push(next);
+                *done = self.target.is_some_and(|(t, _)| t == next);
+
+                if !carved[next] {
+                    carved[next] = true;
+                    if let Some(remaining) = &mut remaining {
+                        *remaining -= 1;
+                        if *remaining == 0 {
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+        (paths, carved)
+    }
+
+    fn step(&self, rng: &mut StdRng, pos: IVec2) -> Option<IVec2> {
+        let moves: Vec<IVec2> = DIRECTIONS
+            .iter()
+            .map(|&d| pos + d)
+            .filter(|&next| in_bounds(self.size, next))
+            .collect();
+
+        if let Some((target, bias)) = self.target
+            && rng.random_bool(f64::from(bias))
+        {
+            let closer: Vec<IVec2> = moves
+                .iter()
+                .copied()
+                .filter(|&next| {
+                    (next - target).abs().element_sum() < (pos - target).abs().element_sum()
+                })
+                .collect();
+            if let Some(&next) = closer.choose(rng) {
+                return Some(next);
+            }
+        }
+        moves.choose(rng).copied()
+    }
+}
+
+fn check_inside(size: UVec2, pos: IVec2, name: &str) {
+    assert!(
+        in_bounds(size, pos),
+        "{name} {pos} is outside the {size} grid"
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EntityRng, GlobalRng};
+
+    #[test]
+    fn paths_step_between_neighbors_inside_the_grid() {
+        let mut rng = GlobalRng::seeded(1);
+        let size = UVec2::new(8, 5);
+        let paths = RandomWalk::new(size, IVec2::new(0, 4))
+            .with_steps(200)
+            .with_walkers(3)
+            .paths(&mut rng);
+
+        assert_eq!(paths.len(), 3);
+        for path in &paths {
+            assert_eq!(path.len(), 201);
+            assert_eq!(path[0], IVec2::new(0, 4));
+            assert!(path.iter().all(|&p| in_bounds(size, p)));
+            assert!(
+                path.windows(2)
+                    .all(|w| (w[1] - w[0]).abs().element_sum() == 1)
+            );
+        }
+    }
+
+    #[test]
+    fn biased_walks_end_at_the_target() {
+        let mut rng = EntityRng::seeded(2);
+        let size = UVec2::new(40, 40);
+        let target = IVec2::new(35, 2);
+
+        let direct = RandomWalk::new(size, IVec2::new(3, 30))
+            .with_target(target, 1.0)
+            .paths(&mut rng);
+        // Full bias never wastes a step
+        assert_eq!(direct[0].len(), 32 + 28 + 1);
+
+        let meandering = RandomWalk::new(size, IVec2::new(3, 30))
+            .with_target(target, 0.5)
+            .paths(&mut rng);
+        assert_eq!(meandering[0].last(), Some(&target));
+        assert!(meandering[0].len() > direct[0].len());
+    }
+
+    #[test]
+    fn carving_stops_at_the_limit() {
+        let walk = RandomWalk::new(UVec2::new(30, 20), IVec2::new(15, 10))
+            .with_walkers(5)
+            .with_carve_limit(150);
+
+        let carved = walk.carve(&mut GlobalRng::seeded(3));
+        assert_eq!(carved.count(|&open| open), 150);
+
+        // The grid marks exactly the cells on the paths
+        let paths = walk.paths(&mut GlobalRng::seeded(3));
+        for (pos, &open) in carved.iter() {
+            assert_eq!(open, paths.iter().flatten().any(|&p| p == pos));
+        }
+    }
+
+    #[test]
+    fn walks_are_reproducible() {
+        let walk = RandomWalk::new(UVec2::new(16, 16), IVec2::splat(8)).with_walkers(2);
+
+        let a = walk.carve(&mut GlobalRng::seeded(4));
+        let b = walk.carve(&mut EntityRng::seeded(4));
+        let c = walk.carve(&mut GlobalRng::seeded(5));
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    #[should_panic(expected = "outside")]
+    fn start_outside_the_grid_panics() {
+        let _ = RandomWalk::new(UVec2::new(4, 4), IVec2::new(4, 0));
+    }
+}
@@ -0,0 +1,434 @@
+//! Weighted selection for [`GlobalRng`] and [`EntityRng`].
+//!
+//! Weights are supplied by a closure, so items don't need a weight field or a
+//! parallel array. All weighted APIs report problems through [`WeightedError`].
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::fmt;
+
+use rand::{Rng, RngExt};
+
+use crate::{EntityRng, GlobalRng};
+
+/// Error returned by the weighted selection methods.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WeightedError {
+    /// There were no items to choose from.
+    Empty,
+    /// A weight was negative, NaN or infinite.
+    InvalidWeight,
+    /// Every weight was zero, so nothing can be chosen.
+    AllWeightsZero,
+}
+
+impl fmt::Display for WeightedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "no items to choose from"),
+            Self::InvalidWeight => write!(f, "weight is negative, NaN or infinite"),
+            Self::AllWeightsZero => write!(f, "all weights are zero"),
+        }
+    }
+}
+
+impl std::error::Error for WeightedError {}
+
+pub(crate) fn checked_weight(weight: f64) -> Result<f64, WeightedError> {
+    if weight >= 0.0 && weight.is_finite() {
+        Ok(weight)
+    } else {
+        Err(WeightedError::InvalidWeight)
+    }
+}
+
+/// Efraimidis–Spirakis key: items with larger keys come first.
+///
+/// Zero-weight items get `-inf`, so they sort after every positive weight.
+fn es_key<R: Rng + ?Sized>(rng: &mut R, weight: f64) -> f64 {
+    // ln(1 - u) stays finite for u in [0, 1)
+    let log_u = (-rng.random::<f64>()).ln_1p();
+    if weight > 0.0 {
+        log_u / weight
+    } else {
+        f64::NEG_INFINITY
+    }
+}
+
+/// Heap entry ordered so the smallest key sits on top.
+struct MinKey(f64, usize);
+
+impl PartialEq for MinKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for MinKey {}
+
+impl PartialOrd for MinKey {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for MinKey {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.0.total_cmp(&self.0)
+    }
+}
+
+pub(crate) fn choose_weighted<'a, R, T, F>(
+    rng: &mut R,
+    slice: &'a [T],
+    weight: F,
+) -> Result<&'a T, WeightedError>
+where
+    R: Rng + ?Sized,
+    F: Fn(&T) -> f64,
+{
+    if slice.is_empty() {
+        return Err(WeightedError::Empty);
+    }
+
+    // Call `weight` once per item so every weight used is a checked one
+    let mut cumulative = Vec::with_capacity(slice.len());
+    let mut total = 0.0;
+    let mut last_positive = 0;
+    for (idx, item) in slice.iter().enumerate() {
+        let w = checked_weight(weight(item))?;
+        if w > 0.0 {
+            last_positive = idx;
+        }
+        total += w;
+        cumulative.push(total);
+    }
+    if total <= 0.0 {
+        return Err(WeightedError::AllWeightsZero);
+    }
+
+    let target = rng.random::<f64>() * total;
+    // Zero-weight items never start a new span, so they are never found.
+    // Floating point rounding can leave a sliver past the last item.
+    let idx = cumulative
+        .partition_point(|&c| c <= target)
+        .min(last_positive);
+    Ok(&slice[idx])
+}
+
+fn sample_weighted<'a, R, T, F>(
+    rng: &mut R,
+    slice: &'a [T],
+    k: usize,
+    weight: F,
+) -> Result<Vec<&'a T>, WeightedError>
+where
+    R: Rng + ?Sized,
+    F: Fn(&T) -> f64,
+{
+    if slice.is_empty() {
+        return Err(WeightedError::Empty);
+    }
+
+    let mut heap = BinaryHeap::with_capacity(k.min(slice.len()) + 1);
+    let mut any_positive = false;
+    for (idx, item) in slice.iter().enumerate() {
+        let w = checked_weight(weight(item))?;
+        let key = es_key(rng, w);
+        any_positive |= w > 0.0;
+        if w > 0.0 && k > 0 {
+            heap.push(MinKey(key, idx));
+            if heap.len() > k {
+                heap.pop();
+            }
+        }
+    }
+    if !any_positive {
+        return Err(WeightedError::AllWeightsZero);
+    }
+
+    // `into_sorted_vec` is ascending by `Ord`, which is descending by key
+    Ok(heap
+        .into_sorted_vec()
+        .into_iter()
+        .map(|MinKey(_, idx)| &slice[idx])
+        .collect())
+}
+
+fn shuffle_weighted<R, T, F>(rng: &mut R, slice: &mut [T], weight: F) -> Result<(), WeightedError>
+where
+    R: Rng + ?Sized,
+    F: Fn(&T) -> f64,
+{
+    let mut keyed = Vec::with_capacity(slice.len());
+    for (idx, item) in slice.iter().enumerate() {
+        let w = checked_weight(weight(item))?;
+        keyed.push((es_key(rng, w), idx));
+    }
+    // Stable sort keeps zero-weight items in their original relative order
+    keyed.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+    // `dest[src]` is the final position of the item currently at `src`
+    let mut dest = vec![0; slice.len()];
+    for (position, &(_, src)) in keyed.iter().enumerate() {
+        dest[src] = position;
+    }
+    for idx in 0..slice.len() {
+        while dest[idx] != idx {
+            let target = dest[idx];
+            slice.swap(idx, target);
+            dest.swap(idx, target);
+        }
+    }
+    Ok(())
+}
+
+impl GlobalRng {
+    /// Select a random element from a slice, with probability proportional
+    /// to its weight.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`WeightedError::Empty`] if the slice is empty,
+    /// [`WeightedError::InvalidWeight`] if any weight is negative or not
+    /// finite, and [`WeightedError::AllWeightsZero`] if nothing has a
+    /// positive weight.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use msg_rng::GlobalRng;
+    /// # let mut rng = GlobalRng::seeded(42);
+    /// let loot = [("common", 70.0), ("rare", 25.0), ("epic", 5.0)];
+    /// let (name, _) = rng.choose_weighted(&loot, |(_, w)| *w).unwrap();
+    /// ```
+    pub fn choose_weighted<'a, T, F>(
+        &mut self,
+        slice: &'a [T],
+        weight: F,
+    ) -> Result<&'a T, WeightedError>
+    where
+        F: Fn(&T) -> f64,
+    {
+        choose_weighted(&mut self.rng, slice, weight)
+    }
+
+    /// Select `k` distinct elements, each pick proportional to weight among
+    /// the remaining elements.
+    ///
+    /// Elements are returned in pick order. Zero-weight elements are never
+    /// picked, so fewer than `k` may be returned.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as
+    /// [`choose_weighted`](Self::choose_weighted): [`WeightedError::Empty`]
+    /// if the slice is empty, [`WeightedError::InvalidWeight`] if any weight
+    /// is negative or not finite, and [`WeightedError::AllWeightsZero`] if
+    /// nothing has a positive weight. Asking for `k = 0` from valid items
+    /// returns an empty `Vec`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use msg_rng::GlobalRng;
+    /// # let mut rng = GlobalRng::seeded(42);
+    /// let upgrades = [("damage", 10.0), ("speed", 10.0), ("vampirism", 1.0), ("shield", 5.0)];
+    /// let offered = rng.sample_weighted(&upgrades, 3, |(_, w)| *w).unwrap();
+    /// assert_eq!(offered.len(), 3);
+    /// ```
+    pub fn sample_weighted<'a, T, F>(
+        &mut self,
+        slice: &'a [T],
+        k: usize,
+        weight: F,
+    ) -> Result<Vec<&'a T>, WeightedError>
+    where
+        F: Fn(&T) -> f64,
+    {
+        sample_weighted(&mut self.rng, slice, k, weight)
+    }
+
+    /// Shuffle a slice in place so heavier elements tend to come first.
+    ///
+    /// Zero-weight elements end up last, in their original relative order.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`WeightedError::InvalidWeight`] if any weight is negative or
+    /// not finite. The slice is left unchanged in that case.
+    pub fn shuffle_weighted<T, F>(
+        &mut self,
+        slice: &mut [T],
+        weight: F,
+    ) -> Result<(), WeightedError>
+    where
+        F: Fn(&T) -> f64,
+    {
+        shuffle_weighted(&mut self.rng, slice, weight)
+    }
+}
+
+impl EntityRng {
+    /// Select a random element from a slice, weighted by `weight`.
+    ///
+    /// # Errors
+    ///
+    /// See [`GlobalRng::choose_weighted`].
+    pub fn choose_weighted<'a, T, F>(
+        &mut self,
+        slice: &'a [T],
+        weight: F,
+    ) -> Result<&'a T, WeightedError>
+    where
+        F: Fn(&T) -> f64,
+    {
+        choose_weighted(&mut self.rng, slice, weight)
+    }
+
+    /// Select `k` distinct elements weighted by `weight`.
+    ///
+    /// # Errors
+    ///
+    /// See [`GlobalRng::sample_weighted`].
+    pub fn sample_weighted<'a, T, F>(
+        &mut self,
+        slice: &'a [T],
+        k: usize,
+        weight: F,
+    ) -> Result<Vec<&'a T>, WeightedError>
+    where
+        F: Fn(&T) -> f64,
+    {
+        sample_weighted(&mut self.rng, slice, k, weight)
+    }
+
+    /// Shuffle a slice in place so heavier elements tend to come first.
+    ///
+    /// # Errors
+    ///
+    /// See [`GlobalRng::shuffle_weighted`].
+    pub fn shuffle_weighted<T, F>(
+        &mut self,
+        slice: &mut [T],
+        weight: F,
+    ) -> Result<(), WeightedError>
+    where
+        F: Fn(&T) -> f64,
+    {
+        shuffle_weighted(&mut self.rng, slice, weight)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn choose_weighted_follows_weights() {
+        let mut rng = GlobalRng::seeded(42);
+        let items = [(0, 1.0), (1, 3.0), (2, 0.0)];
+        let mut counts = [0u32; 3];
+
+        for _ in 0..10_000 {
+            let (idx, _) = rng.choose_weighted(&items, |(_, w)| *w).unwrap();
+            counts[*idx] += 1;
+        }
+
+        assert_eq!(counts[2], 0);
+        assert!((2300..2700).contains(&counts[0]), "{counts:?}");
+    }
+
+    #[test]
+    fn weighted_errors_are_reported() {
+        let mut rng = EntityRng::seeded(1);
+
+        let empty: [f64; 0] = [];
+        assert_eq!(
+            rng.choose_weighted(&empty, |w| *w),
+            Err(WeightedError::Empty)
+        );
+        assert_eq!(
+            rng.choose_weighted(&[0.0, 0.0], |w| *w),
+            Err(WeightedError::AllWeightsZero)
+        );
+        assert_eq!(
+            rng.sample_weighted(&[1.0, -1.0], 1, |w| *w),
+            Err(WeightedError::InvalidWeight)
+        );
+        // Sampling shares the input contract of choosing
+        assert_eq!(
+            rng.sample_weighted(&empty, 2, |w| *w),
+            Err(WeightedError::Empty)
+        );
+        assert_eq!(
+            rng.sample_weighted(&[0.0, 0.0], 2, |w| *w),
+            Err(WeightedError::AllWeightsZero)
+        );
+        assert_eq!(rng.sample_weighted(&[1.0, 2.0], 0, |w| *w), Ok(vec![]));
+
+        let mut items = [1.0, f64::NAN, 2.0];
+        assert_eq!(
+            rng.shuffle_weighted(&mut items, |w| *w),
+            Err(WeightedError::InvalidWeight)
+        );
+        assert!((items[0] - 1.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn choose_weighted_calls_weight_once_per_item() {
+        let mut rng = GlobalRng::seeded(5);
+        let calls = std::cell::Cell::new(0);
+        // A second call would see NaN, which must never reach the selection
+        let weight = |w: &f64| {
+            calls.set(calls.get() + 1);
+            if calls.get() > 3 { f64::NAN } else { *w }
+        };
+
+        assert!(rng.choose_weighted(&[1.0, 2.0, 3.0], weight).is_ok());
+        assert_eq!(calls.get(), 3);
+    }
+
+    #[test]
+    fn sample_weighted_returns_distinct_positive_items() {
+        let mut rng = GlobalRng::seeded(7);
+        let items = [(0, 5.0), (1, 0.0), (2, 1.0), (3, 2.0)];
+
+        let picked = rng.sample_weighted(&items, 10, |(_, w)| *w).unwrap();
+        let mut ids: Vec<i32> = picked.iter().map(|(id, _)| *id).collect();
+        ids.sort_unstable();
+        assert_eq!(ids, vec![0, 2, 3]);
+    }
+
+    #[test]
+    fn sample_weighted_prefers_heavy_items() {
+        let mut rng = GlobalRng::seeded(9);
+        let items = [(0, 100.0), (1, 1.0), (2, 1.0), (3, 1.0)];
+        let mut heavy_first = 0;
+
+        for _ in 0..1000 {
+            let picked = rng.sample_weighted(&items, 2, |(_, w)| *w).unwrap();
+            if picked[0].0 == 0 {
+                heavy_first += 1;
+            }
+        }
+
+        // P(heavy first) = 100 / 103
+        assert!(heavy_first > 950, "{heavy_first}");
+    }
+
+    #[test]
+    fn shuffle_weighted_is_a_permutation() {
+        let mut rng = GlobalRng::seeded(3);
+        let mut items: Vec<u32> = (0..50).collect();
+
+        rng.shuffle_weighted(&mut items, |&n| if n < 5 { 0.0 } else { f64::from(n) })
+            .unwrap();
+
+        let mut sorted = items.clone();
+        sorted.sort_unstable();
+        assert_eq!(sorted, (0..50).collect::<Vec<_>>());
+        // Zero-weight items go last, in original order
+        assert_eq!(&items[45..], &[0, 1, 2, 3, 4]);
+    }
+}
@@ -0,0 +1,141 @@
+//! Tests for `#[derive(Random)]`
+
+use msg_rng::Random;
+use msg_rng::prelude::*;
+
+#[derive(Random, Debug, Clone, Copy, PartialEq, Eq)]
+enum Biome {
+    #[weight(6)]
+    Plains,
+    #[weight(3)]
+    Forest,
+    #[weight(1)]
+    Volcano,
+    #[weight(0)]
+    Void,
+}
+
+#[derive(Random, Debug, PartialEq, Eq)]
+enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+#[derive(Random, Debug, PartialEq)]
+struct Stats {
+    #[range(1..=20)]
+    strength: u32,
+    #[range(0.5..1.5)]
+    speed: f32,
+    lucky: bool,
+}
+
+#[derive(Random, Debug)]
+struct Loot(#[range(10..100)] u32, Biome);
+
+#[derive(Random, Debug)]
+enum Event {
+    Nothing,
+    Ambush {
+        #[range(1..=5)]
+        enemies: u8,
+    },
+    Treasure(#[range(1..=3)] u8),
+}
+
+#[derive(Random, Debug)]
+struct Pair<T> {
+    first: T,
+    second: T,
+}
+
+#[test]
+fn weighted_enum_follows_weights() {
+    let mut rng = GlobalRng::seeded(42);
+    let mut counts = [0u32; 4];
+
+    for _ in 0..10_000 {
+        let biome: Biome = rng.random_value();
+        counts[biome as usize] += 1;
+    }
+
+    assert_eq!(counts[Biome::Void as usize], 0);
+    assert!((5700..6300).contains(&counts[0]), "{counts:?}");
+    assert!((2700..3300).contains(&counts[1]), "{counts:?}");
+    assert!((800..1200).contains(&counts[2]), "{counts:?}");
+}
+
+#[test]
+fn unweighted_enum_covers_all_variants() {
+    let mut rng = EntityRng::seeded(1);
+    let seen: Vec<Direction> = (0..100).map(|_| rng.random_value()).collect();
+
+    for direction in [
+        Direction::North,
+        Direction::East,
+        Direction::South,
+        Direction::West,
+    ] {
+        assert!(seen.contains(&direction));
+    }
+}
+
+#[test]
+fn struct_fields_respect_ranges() {
+    let mut rng = GlobalRng::seeded(7);
+
+    for _ in 0..1000 {
+        let stats: Stats = rng.random_value();
+        assert!((1..=20).contains(&stats.strength));
+        assert!((0.5..1.5).contains(&stats.speed));
+
+        let Loot(gold, biome) = rng.random_value();
+        assert!((10..100).contains(&gold));
+        assert_ne!(biome, Biome::Void);
+    }
+}
+
+#[test]
+fn enum_variant_fields_are_sampled() {
+    let mut rng = GlobalRng::seeded(9);
+
+    for _ in 0..1000 {
+        match rng.random_value::<Event>() {
+            Event::Nothing => {}
+            Event::Ambush { enemies } => assert!((1..=5).contains(&enemies)),
+            Event::Treasure(chests) => assert!((1..=3).contains(&chests)),
+        }
+    }
+}
+
+#[test]
+fn derived_values_are_deterministic() {
+    let mut rng1 = GlobalRng::seeded(11);
+    let mut rng2 = GlobalRng::seeded(11);
+
+    let a: Vec<Stats> = (0..10).map(|_| rng1.random_value()).collect();
+    let b: Vec<Stats> = (0..10).map(|_| rng2.random_value()).collect();
+    assert_eq!(a, b);
+
+    let pair: Pair<u8> = rng1.random_value();
+    let _ = (pair.first, pair.second);
+}
+
+#[derive(Random, Debug, Clone, Copy, PartialEq, Eq)]
+enum Rarity {
+    #[weight(0.75)]
+    Common,
+    #[weight(0.25)]
+    Rare,
+}
+
+#[test]
+fn fractional_weights_are_supported() {
+    let mut rng = GlobalRng::seeded(13);
+    let rare = (0..10_000)
+        .filter(|_| rng.random_value::<Rarity>() == Rarity::Rare)
+        .count();
+    assert!((2200..2800).contains(&rare), "{rare}");
+}
@@ -127,6 +127,65 @@ fn test_type_alias_system(mut rng: GlobalRngMut) {
     assert_eq!(rng.seed(), 999);
 }
 
+#[derive(Component)]
+struct Threat(f64);
+
+fn spawn_threats_system(mut commands: Commands) {
+    for threat in [0.0, 1.0, 2.0, 0.0] {
+        commands.spawn(Threat(threat));
+    }
+}
+
+fn pick_from_query_system(query: Query<(Entity, &Threat)>, mut rng: ResMut<GlobalRng>) {
+    assert!(query.iter().choose_with(&mut rng).is_some());
+
+    let (_, threat) = query
+        .iter()
+        .choose_weighted_by(|(_, threat)| threat.0, &mut rng)
+        .unwrap();
+    assert!(threat.0 > 0.0);
+
+    let picked = query.iter().sample_with(2, &mut rng);
+    assert_eq!(picked.len(), 2);
+    assert_ne!(picked[0].0, picked[1].0);
+}
+
+fn pick_with_entity_rng_system(mut rngs: Query<&mut EntityRng>, threats: Query<&Threat>) {
+    for mut rng in &mut rngs {
+        assert!(threats.iter().choose_with(&mut rng).is_some());
+    }
+}
+
+#[derive(Resource, Default)]
+struct TimerCounts {
+    messages: u32,
+    observed: u32,
+}
+
+fn spawn_random_timer_system(mut commands: Commands) {
+    commands.spawn((
+        EntityRng::seeded(1100),
+        RandomTimer::repeating(TimerInterval::Uniform { min: 1.0, max: 1.0 }),
+    ));
+}
+
+fn count_timer_messages_system(
+    mut finished: MessageReader<RandomTimerFinished>,
+    mut counts: ResMut<TimerCounts>,
+) {
+    counts.messages += u32::try_from(finished.read().count()).unwrap();
+}
+
+#[derive(Resource, Default)]
+struct SchedulerCount(u64);
+
+fn count_scheduled_events_system(
+    mut fired: MessageReader<ScheduledEventFired>,
+    mut count: ResMut<SchedulerCount>,
+) {
+    count.0 += fired.read().count() as u64;
+}
+
 // Tests
 #[test]
 fn plugin_initializes_with_seeded_rng() {
@@ -292,3 +351,84 @@ fn seeded_plugin_is_deterministic_across_apps() {
         app2.world().resource::<GlobalRng>().seed()
     );
 }
+
+#[test]
+fn random_selection_from_queries() {
+    let mut app = App::new();
+    app.add_plugins(RngPlugin::seeded(1000));
+
+    app.add_systems(Startup, (spawn_threats_system, spawn_entity_rng_system));
+    app.add_systems(
+        Update,
+        (pick_from_query_system, pick_with_entity_rng_system),
+    );
+    app.update();
+    app.update();
+}
+
+#[test]
+fn random_timers_send_messages_and_trigger_observers() {
+    let mut app = App::new();
+    app.add_plugins((RngPlugin::seeded(1100), RandomTimerPlugin));
+    app.init_resource::<Time>();
+    app.init_resource::<TimerCounts>();
+
+    app.add_observer(
+        |_finished: On<RandomTimerFinished>, mut counts: ResMut<TimerCounts>| {
+            counts.observed += 1;
+        },
+    );
+    app.add_systems(Startup, spawn_random_timer_system);
+    app.add_systems(PostUpdate, count_timer_messages_system);
+
+    for _ in 0..20 {
+        app.world_mut()
+            .resource_mut::<Time>()
+            .advance_by(std::time::Duration::from_millis(250));
+        app.update();
+    }
+
+    let counts = app.world().resource::<TimerCounts>();
+    assert_eq!(counts.messages, 5);
+    assert_eq!(counts.observed, 5);
+}
+
+#[test]
+fn event_scheduler_follows_virtual_time() {
+    use bevy::time::{TimePlugin, TimeUpdateStrategy};
+
+    let mut app = App::new();
+    app.add_plugins((TimePlugin, RngPlugin::seeded(1200), EventSchedulerPlugin));
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(
+        std::time::Duration::from_millis(100),
+    ));
+    app.init_resource::<SchedulerCount>();
+    app.add_systems(PostUpdate, count_scheduled_events_system);
+    app.world_mut()
+        .resource_mut::<EventScheduler>()
+        .insert("spark", 5.0);
+
+    for _ in 0..100 {
+        app.update();
+    }
+    let elapsed = app.world().resource::<EventScheduler>().elapsed();
+    // The scheduler accumulates seconds as f64, so allow for rounding
+    let virtual_elapsed = app.world().resource::<Time<Virtual>>().elapsed();
+    assert!(
+        elapsed.abs_diff(virtual_elapsed) < std::time::Duration::from_micros(1),
+        "{elapsed:?} vs {virtual_elapsed:?}"
+    );
+    assert!(elapsed > std::time::Duration::from_secs(9));
+
+    app.world_mut().resource_mut::<Time<Virtual>>().pause();
+    for _ in 0..100 {
+        app.update();
+    }
+    let scheduler = app.world().resource::<EventScheduler>();
+    // Paused time adds nothing, so the stored value is unchanged
+    assert_eq!(scheduler.elapsed(), elapsed);
+    assert_eq!(
+        scheduler.occurrences("spark"),
+        Some(app.world().resource::<SchedulerCount>().0)
+    );
+}